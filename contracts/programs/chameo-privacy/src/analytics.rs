@@ -1,24 +1,63 @@
 use anchor_lang::prelude::*;
 use inco_lightning::cpi::accounts::{Operation, Allow};
 use inco_lightning::cpi::{new_euint128, as_euint128, e_add, allow};
-use inco_lightning::types::Euint128;
 use inco_lightning::ID as INCO_LIGHTNING_ID;
 use crate::ErrorCode;
+use crate::events::{
+    AccountMigrated, AnalyticsAccessGranted, AnalyticsEventTracked, AnalyticsInitialized,
+    EVENT_SCHEMA_VERSION,
+};
+use crate::handle::IncoHandle;
+use crate::migration;
 
+// New fields must be appended so `migrate_analytics` can zero-extend older accounts.
 #[account]
+#[derive(InitSpace)]
 pub struct Analytics {
+    pub version: u8,
     pub campaign_id: [u8; 32],
     pub authority: Pubkey,
-    pub page_views: Euint128,
-    pub link_clicks: Euint128,
-    pub claim_starts: Euint128,
-    pub claim_successes: Euint128,
-    pub claim_failures: Euint128,
-    pub votes: Euint128,
+    pub page_views: IncoHandle,
+    pub link_clicks: IncoHandle,
+    pub claim_starts: IncoHandle,
+    pub claim_successes: IncoHandle,
+    pub claim_failures: IncoHandle,
+    pub votes: IncoHandle,
 }
 
 impl Analytics {
-    pub const LEN: usize = 32 + 32 + 16 * 6;
+    pub const VERSION: u8 = 1;
+}
+
+/// Analytics layout from before accounts carried a version byte.
+#[derive(AnchorDeserialize)]
+struct LegacyAnalytics {
+    campaign_id: [u8; 32],
+    authority: Pubkey,
+    page_views: IncoHandle,
+    link_clicks: IncoHandle,
+    claim_starts: IncoHandle,
+    claim_successes: IncoHandle,
+    claim_failures: IncoHandle,
+    votes: IncoHandle,
+}
+
+impl LegacyAnalytics {
+    const LEN: usize = 32 + 32 + 16 * 6;
+
+    fn into_current(self) -> Analytics {
+        Analytics {
+            version: 0,
+            campaign_id: self.campaign_id,
+            authority: self.authority,
+            page_views: self.page_views,
+            link_clicks: self.link_clicks,
+            claim_starts: self.claim_starts,
+            claim_successes: self.claim_successes,
+            claim_failures: self.claim_failures,
+            votes: self.votes,
+        }
+    }
 }
 
 #[derive(Accounts)]
@@ -27,7 +66,7 @@ pub struct InitializeAnalytics<'info> {
     #[account(
         init,
         payer = authority,
-        space = 8 + Analytics::LEN,
+        space = 8 + Analytics::INIT_SPACE,
         seeds = [b"analytics", campaign_id.as_ref()],
         bump
     )]
@@ -94,7 +133,23 @@ pub struct GrantAnalyticsAccess<'info> {
     pub system_program: Program<'info, System>,
 }
 
-pub fn initialize_analytics<'info>(
+#[derive(Accounts)]
+#[instruction(campaign_id: [u8; 32])]
+pub struct MigrateAnalytics<'info> {
+    /// CHECK: Decoded by hand because older layouts don't deserialize as `Analytics`
+    #[account(
+        mut,
+        seeds = [b"analytics", campaign_id.as_ref()],
+        bump,
+        owner = crate::ID
+    )]
+    pub analytics: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn initialize_analytics<'info>(
     ctx: Context<'_, '_, '_, 'info, InitializeAnalytics<'info>>,
     campaign_id: [u8; 32],
 ) -> Result<()> {
@@ -102,33 +157,41 @@ pub fn initialize_analytics<'info>(
     let inco = ctx.accounts.inco_lightning_program.to_account_info();
     let signer = ctx.accounts.authority.to_account_info();
     
+    analytics.version = Analytics::VERSION;
     analytics.campaign_id = campaign_id;
     analytics.authority = ctx.accounts.authority.key();
     
     let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
-    analytics.page_views = as_euint128(cpi_ctx, 0)?;
+    analytics.page_views = as_euint128(cpi_ctx, 0)?.into();
     
     let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
-    analytics.link_clicks = as_euint128(cpi_ctx, 0)?;
+    analytics.link_clicks = as_euint128(cpi_ctx, 0)?.into();
     
     let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
-    analytics.claim_starts = as_euint128(cpi_ctx, 0)?;
+    analytics.claim_starts = as_euint128(cpi_ctx, 0)?.into();
 
     let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
-    analytics.claim_successes = as_euint128(cpi_ctx, 0)?;
+    analytics.claim_successes = as_euint128(cpi_ctx, 0)?.into();
 
     let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
-    analytics.claim_failures = as_euint128(cpi_ctx, 0)?;
+    analytics.claim_failures = as_euint128(cpi_ctx, 0)?.into();
 
     let cpi_ctx = CpiContext::new(inco, Operation { signer });
-    analytics.votes = as_euint128(cpi_ctx, 0)?;
+    analytics.votes = as_euint128(cpi_ctx, 0)?.into();
+
+    emit!(AnalyticsInitialized {
+        schema_version: EVENT_SCHEMA_VERSION,
+        campaign_id,
+        analytics: analytics.key(),
+        authority: analytics.authority,
+    });
     
     Ok(())
 }
 
-pub fn track_event<'info>(
+pub(crate) fn track_event<'info>(
     ctx: Context<'_, '_, '_, 'info, TrackEvent<'info>>,
-    _campaign_id: [u8; 32],
+    campaign_id: [u8; 32],
     encrypted_increment: Vec<u8>,
     event_type: u8,
 ) -> Result<()> {
@@ -142,37 +205,44 @@ pub fn track_event<'info>(
     match event_type {
         0 => {
             let cpi_ctx = CpiContext::new(inco, Operation { signer });
-            analytics.page_views = e_add(cpi_ctx, analytics.page_views, increment, 0)?;
+            analytics.page_views = e_add(cpi_ctx, analytics.page_views.into(), increment, 0)?.into();
         }
         1 => {
             let cpi_ctx = CpiContext::new(inco, Operation { signer });
-            analytics.link_clicks = e_add(cpi_ctx, analytics.link_clicks, increment, 0)?;
+            analytics.link_clicks = e_add(cpi_ctx, analytics.link_clicks.into(), increment, 0)?.into();
         }
         2 => {
             let cpi_ctx = CpiContext::new(inco, Operation { signer });
-            analytics.claim_starts = e_add(cpi_ctx, analytics.claim_starts, increment, 0)?;
+            analytics.claim_starts = e_add(cpi_ctx, analytics.claim_starts.into(), increment, 0)?.into();
         }
         3 => {
             let cpi_ctx = CpiContext::new(inco, Operation { signer });
-            analytics.claim_successes = e_add(cpi_ctx, analytics.claim_successes, increment, 0)?;
+            analytics.claim_successes = e_add(cpi_ctx, analytics.claim_successes.into(), increment, 0)?.into();
         }
         4 => {
             let cpi_ctx = CpiContext::new(inco, Operation { signer });
-            analytics.claim_failures = e_add(cpi_ctx, analytics.claim_failures, increment, 0)?;
+            analytics.claim_failures = e_add(cpi_ctx, analytics.claim_failures.into(), increment, 0)?.into();
         }
         5 => {
             let cpi_ctx = CpiContext::new(inco, Operation { signer });
-            analytics.votes = e_add(cpi_ctx, analytics.votes, increment, 0)?;
+            analytics.votes = e_add(cpi_ctx, analytics.votes.into(), increment, 0)?.into();
         }
         _ => return Err(ErrorCode::InvalidEventType.into()),
     }
+
+    emit!(AnalyticsEventTracked {
+        schema_version: EVENT_SCHEMA_VERSION,
+        campaign_id,
+        analytics: analytics.key(),
+        event_type,
+    });
     
     Ok(())
 }
 
-pub fn grant_analytics_access<'info>(
+pub(crate) fn grant_analytics_access<'info>(
     ctx: Context<'_, '_, '_, 'info, GrantAnalyticsAccess<'info>>,
-    _campaign_id: [u8; 32],
+    campaign_id: [u8; 32],
     allowed_address: Pubkey,
 ) -> Result<()> {
     let analytics = &ctx.accounts.analytics;
@@ -245,5 +315,57 @@ pub fn grant_analytics_access<'info>(
     );
     allow(cpi_ctx, analytics.votes.0, true, allowed_address)?;
 
+    emit!(AnalyticsAccessGranted {
+        schema_version: EVENT_SCHEMA_VERSION,
+        campaign_id,
+        analytics: analytics.key(),
+        allowed_address,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn migrate_analytics<'info>(
+    ctx: Context<'_, '_, '_, 'info, MigrateAnalytics<'info>>,
+    campaign_id: [u8; 32],
+) -> Result<()> {
+    let info = ctx.accounts.analytics.to_account_info();
+
+    let mut analytics = if info.data_len() == 8 + LegacyAnalytics::LEN {
+        let data = info.try_borrow_data()?;
+        require!(
+            data[..8] == *Analytics::DISCRIMINATOR,
+            anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch
+        );
+        LegacyAnalytics::deserialize(&mut &data[8..])?.into_current()
+    } else {
+        migration::read_padded::<Analytics>(&info, Analytics::INIT_SPACE)?
+    };
+
+    require!(
+        analytics.authority == ctx.accounts.authority.key(),
+        ErrorCode::Unauthorized
+    );
+    require!(analytics.version < Analytics::VERSION, ErrorCode::AlreadyMigrated);
+
+    let from_version = analytics.version;
+    analytics.version = Analytics::VERSION;
+
+    migration::resize_account(
+        &info,
+        &ctx.accounts.authority.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        8 + Analytics::INIT_SPACE,
+    )?;
+    migration::write_account(&info, &analytics)?;
+
+    emit!(AccountMigrated {
+        schema_version: EVENT_SCHEMA_VERSION,
+        campaign_id,
+        account: info.key(),
+        from_version,
+        to_version: analytics.version,
+    });
+
     Ok(())
 }
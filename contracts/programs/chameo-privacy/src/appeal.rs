@@ -0,0 +1,250 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+use anchor_lang::system_program::{transfer, Transfer};
+use inco_lightning::ID as INCO_LIGHTNING_ID;
+use crate::ErrorCode;
+use crate::escrow::read_escrow;
+use crate::events::{AppealBondSettled, AppealOpened, EVENT_SCHEMA_VERSION};
+use crate::voting::{setup_voting_pool, VoteOutcome, VotingPool, APPEAL_WINDOW_SECONDS};
+
+/// Campaign id an appeal round's pool is keyed by.
+///
+/// Rounds are ordinary voting pools, so deriving a fresh id gives each its own
+/// PDA and nullifier set while every voting instruction works unchanged.
+pub fn appeal_campaign_id(campaign_id: &[u8; 32], round: u8) -> [u8; 32] {
+    hashv(&[b"appeal", campaign_id.as_ref(), &[round]]).to_bytes()
+}
+
+/// Id of a dispute's pool at `round`, following the chain of appeal ids.
+pub fn round_campaign_id(campaign_id: &[u8; 32], round: u8) -> [u8; 32] {
+    (1..=round).fold(*campaign_id, |id, round| appeal_campaign_id(&id, round))
+}
+
+#[derive(Accounts)]
+#[instruction(campaign_id: [u8; 32], round_campaign_id: [u8; 32])]
+pub struct AppealVoting<'info> {
+    #[account(
+        mut,
+        seeds = [b"voting_pool", campaign_id.as_ref()],
+        bump
+    )]
+    pub parent_pool: Account<'info, VotingPool>,
+    #[account(
+        init,
+        payer = appellant,
+        space = 8 + VotingPool::INIT_SPACE,
+        seeds = [b"voting_pool", round_campaign_id.as_ref()],
+        bump
+    )]
+    pub voting_pool: Account<'info, VotingPool>,
+    #[account(mut)]
+    pub appellant: Signer<'info>,
+    /// CHECK: Inco Lightning program
+    #[account(address = INCO_LIGHTNING_ID)]
+    pub inco_lightning_program: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(campaign_id: [u8; 32], round_campaign_id: [u8; 32])]
+pub struct SettleAppealBond<'info> {
+    #[account(
+        mut,
+        seeds = [b"voting_pool", round_campaign_id.as_ref()],
+        bump
+    )]
+    pub voting_pool: Account<'info, VotingPool>,
+    #[account(address = voting_pool.parent_pool @ ErrorCode::InvalidAppealRound)]
+    pub parent_pool: Account<'info, VotingPool>,
+    /// CHECK: The campaign's escrow address, which need not hold an escrow
+    #[account(
+        seeds = [b"escrow", campaign_id.as_ref()],
+        bump
+    )]
+    pub escrow: UncheckedAccount<'info>,
+    /// CHECK: The appellant on a reversal, otherwise the forfeit destination
+    #[account(mut)]
+    pub recipient: AccountInfo<'info>,
+    pub requester: Signer<'info>,
+}
+
+/// Opens the next round of a closed dispute. The appellant's bond is held on
+/// the new round's account until `settle_appeal_bond`.
+///
+/// The appellant signs and pays alone, so a host can't block appeals against
+/// its own outcome. The round stays under the parent's authority and starts
+/// with its eligibility set and verifier; the authority can still replace the
+/// set or draw a jury before anyone votes.
+pub(crate) fn appeal_voting<'info>(
+    ctx: Context<'_, '_, '_, 'info, AppealVoting<'info>>,
+    campaign_id: [u8; 32],
+    round_campaign_id: [u8; 32],
+    window_seconds: i64,
+) -> Result<()> {
+    let parent_pool = &ctx.accounts.parent_pool;
+    require!(!parent_pool.is_active, ErrorCode::VotingStillActive);
+    // Escrows release and outcome hooks run once the window has passed.
+    require!(
+        parent_pool.appeal_open(Clock::get()?.unix_timestamp),
+        ErrorCode::AppealUnavailable
+    );
+    // The appellant picks the window, so it can't be short enough to void the round.
+    require!(
+        window_seconds >= APPEAL_WINDOW_SECONDS,
+        ErrorCode::InvalidPoolOptions
+    );
+
+    let round = parent_pool.round + 1;
+    require!(
+        round_campaign_id == appeal_campaign_id(&campaign_id, round),
+        ErrorCode::InvalidAppealRound
+    );
+
+    let options = parent_pool.options();
+    let authority = parent_pool.authority;
+    let eligibility_root = parent_pool.eligibility_root;
+    let zk_verifier_program = parent_pool.zk_verifier_program;
+
+    let inco = ctx.accounts.inco_lightning_program.to_account_info();
+    let signer = ctx.accounts.appellant.to_account_info();
+    setup_voting_pool(
+        &mut ctx.accounts.voting_pool,
+        &inco,
+        &signer,
+        authority,
+        round_campaign_id,
+        eligibility_root,
+        zk_verifier_program,
+        &options,
+    )?;
+
+    let appellant = ctx.accounts.appellant.key();
+    let appeal_bond = parent_pool.appeal_bond;
+    if appeal_bond > 0 {
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.appellant.to_account_info(),
+                to: ctx.accounts.voting_pool.to_account_info(),
+            },
+        );
+        transfer(cpi_ctx, appeal_bond)?;
+    }
+
+    let voting_ends_at = Clock::get()?.unix_timestamp + window_seconds;
+    let parent_key = ctx.accounts.parent_pool.key();
+    let round_pool = &mut ctx.accounts.voting_pool;
+    round_pool.round = round;
+    round_pool.parent_pool = parent_key;
+    round_pool.appellant = appellant;
+    round_pool.voting_ends_at = voting_ends_at;
+    ctx.accounts.parent_pool.appeal_pool = round_pool.key();
+
+    emit!(AppealOpened {
+        schema_version: EVENT_SCHEMA_VERSION,
+        campaign_id,
+        parent_pool: parent_key,
+        voting_pool: round_pool.key(),
+        round,
+        round_campaign_id,
+        appellant,
+        appeal_bond,
+        eligibility_root,
+        voting_ends_at,
+    });
+
+    Ok(())
+}
+
+/// Pays out the bond held on a closed appeal round. Anyone may call it once the
+/// round has an outcome.
+///
+/// The appellant gets the bond back when the round reversed its parent's
+/// outcome. Otherwise it is forfeited to the escrow's refund address, or to
+/// the pool authority when the campaign has no escrow.
+pub(crate) fn settle_appeal_bond<'info>(
+    ctx: Context<'_, '_, '_, 'info, SettleAppealBond<'info>>,
+    campaign_id: [u8; 32],
+    round_campaign_id: [u8; 32],
+) -> Result<()> {
+    let voting_pool = &ctx.accounts.voting_pool;
+    require!(voting_pool.round > 0, ErrorCode::InvalidAppealRound);
+    require!(
+        round_campaign_id == self::round_campaign_id(&campaign_id, voting_pool.round),
+        ErrorCode::InvalidAppealRound
+    );
+    require!(!voting_pool.is_active, ErrorCode::VotingStillActive);
+    let parent_outcome = ctx.accounts.parent_pool.outcome;
+    require!(
+        voting_pool.outcome != VoteOutcome::Pending && parent_outcome != VoteOutcome::Pending,
+        ErrorCode::OutcomeUnavailable
+    );
+
+    let refunded = voting_pool.outcome != parent_outcome;
+    let expected = if refunded {
+        voting_pool.appellant
+    } else {
+        match read_escrow(&ctx.accounts.escrow)? {
+            Some(escrow) => escrow.refund_address,
+            None => voting_pool.authority,
+        }
+    };
+    require_keys_eq!(ctx.accounts.recipient.key(), expected, ErrorCode::Unauthorized);
+
+    // The round was created rent-exempt, so anything above that is the bond.
+    let info = voting_pool.to_account_info();
+    let rent = Rent::get()?.minimum_balance(info.data_len());
+    let amount = info.lamports().saturating_sub(rent);
+    require!(amount > 0, ErrorCode::AppealUnavailable);
+    **info.try_borrow_mut_lamports()? -= amount;
+    **ctx.accounts.recipient.try_borrow_mut_lamports()? += amount;
+
+    emit!(AppealBondSettled {
+        schema_version: EVENT_SCHEMA_VERSION,
+        campaign_id,
+        voting_pool: info.key(),
+        round: voting_pool.round,
+        recipient: expected,
+        amount,
+        refunded,
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_zero_is_the_campaign_itself() {
+        let campaign_id = [3u8; 32];
+        assert_eq!(round_campaign_id(&campaign_id, 0), campaign_id);
+    }
+
+    #[test]
+    fn rounds_follow_the_chain_of_appeal_ids() {
+        let campaign_id = [3u8; 32];
+        let first = appeal_campaign_id(&campaign_id, 1);
+        assert_eq!(round_campaign_id(&campaign_id, 1), first);
+        assert_eq!(
+            round_campaign_id(&campaign_id, 2),
+            appeal_campaign_id(&first, 2)
+        );
+    }
+
+    #[test]
+    fn rounds_get_distinct_ids() {
+        let campaign_id = [3u8; 32];
+        let ids: Vec<[u8; 32]> = (0..4)
+            .map(|round| round_campaign_id(&campaign_id, round))
+            .collect();
+        for (i, id) in ids.iter().enumerate() {
+            assert!(ids[i + 1..].iter().all(|other| other != id));
+        }
+        assert_ne!(
+            round_campaign_id(&campaign_id, 1),
+            round_campaign_id(&[4u8; 32], 1)
+        );
+    }
+}
@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+use inco_lightning::cpi::accounts::VerifySignature;
+use inco_lightning::cpi::is_validsignature;
+use crate::ErrorCode;
+use crate::handle::IncoHandle;
+
+/// Checks that Inco's covalidator attested `handle` decrypts to `value`.
+///
+/// The attested-decrypt response is submitted as an Ed25519 signature
+/// instruction earlier in the same transaction; Inco reads it back from the
+/// instructions sysvar. Handles and plaintexts are encoded as 128-bit
+/// little-endian, matching the allowance PDA seed.
+pub(crate) fn verify_decryption<'info>(
+    inco: &AccountInfo<'info>,
+    instructions: &AccountInfo<'info>,
+    signer: &AccountInfo<'info>,
+    handle: IncoHandle,
+    value: u128,
+) -> Result<()> {
+    let cpi_ctx = CpiContext::new(
+        inco.clone(),
+        VerifySignature {
+            instructions: instructions.clone(),
+            signer: signer.clone(),
+        },
+    );
+    let results = is_validsignature(
+        cpi_ctx,
+        1,
+        Some(vec![handle.0.to_le_bytes().to_vec()]),
+        Some(vec![value.to_le_bytes().to_vec()]),
+    )?;
+    require!(results.len() == 1, ErrorCode::InvalidDecryptionAttestation);
+    Ok(())
+}
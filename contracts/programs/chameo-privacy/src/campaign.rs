@@ -0,0 +1,323 @@
+use anchor_lang::prelude::*;
+use inco_lightning::ID as INCO_LIGHTNING_ID;
+use crate::ErrorCode;
+use crate::escrow::read_escrow;
+use crate::events::{
+    CampaignEligibilityUpdated, CampaignInitialized, DisputeOpened, WinnersCommitted,
+    EVENT_SCHEMA_VERSION,
+};
+use crate::migration;
+use crate::voting::{
+    dispute_commitment, setup_voting_pool, validate_pool_options, verify_ballot, VotingPool,
+    VotingPoolOptions,
+};
+
+/// On-chain terms of an escrow campaign, so a dispute can open without the server.
+// New fields must be appended, as with `VotingPool`.
+#[account]
+#[derive(InitSpace)]
+pub struct Campaign {
+    pub version: u8,
+    pub campaign_id: [u8; 32],
+    pub authority: Pubkey,
+    pub eligibility_root: [u8; 32],
+    pub zk_verifier_program: Pubkey,
+    /// Settings for the dispute pool; `eligible_count` tracks the root.
+    pub pool_options: VotingPoolOptions,
+    /// Unix time by which the host must select winners.
+    pub winners_deadline: i64,
+    /// Length of a dispute opened by `open_dispute`; 0 leaves the close to the authority.
+    pub dispute_window_seconds: i64,
+    pub winners_selected: bool,
+    pub dispute_opened: bool,
+    /// Merkle root over the winners' identity leaves; zero until committed.
+    pub winners_root: [u8; 32],
+    pub winner_count: u32,
+}
+
+impl Campaign {
+    pub const VERSION: u8 = 1;
+}
+
+#[derive(Accounts)]
+#[instruction(campaign_id: [u8; 32])]
+pub struct InitializeCampaign<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Campaign::INIT_SPACE,
+        seeds = [b"campaign", campaign_id.as_ref()],
+        bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+    /// CHECK: The campaign's dispute pool address, which must still be empty
+    #[account(
+        seeds = [b"voting_pool", campaign_id.as_ref()],
+        bump
+    )]
+    pub voting_pool: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(campaign_id: [u8; 32])]
+pub struct UpdateCampaign<'info> {
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign_id.as_ref()],
+        bump,
+        constraint = campaign.authority == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub campaign: Account<'info, Campaign>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(campaign_id: [u8; 32])]
+pub struct CommitWinners<'info> {
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign_id.as_ref()],
+        bump,
+        constraint = campaign.authority == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub campaign: Account<'info, Campaign>,
+    /// CHECK: The campaign's escrow address; if it holds an escrow, its claims
+    /// then prove against the winners root
+    #[account(
+        mut,
+        seeds = [b"escrow", campaign_id.as_ref()],
+        bump
+    )]
+    pub escrow: UncheckedAccount<'info>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(campaign_id: [u8; 32])]
+pub struct OpenDispute<'info> {
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign_id.as_ref()],
+        bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+    #[account(
+        init,
+        payer = opener,
+        space = 8 + VotingPool::INIT_SPACE,
+        seeds = [b"voting_pool", campaign_id.as_ref()],
+        bump
+    )]
+    pub voting_pool: Account<'info, VotingPool>,
+    #[account(mut)]
+    pub opener: Signer<'info>,
+    /// CHECK: ZK verifier program
+    pub zk_verifier_program: AccountInfo<'info>,
+    /// CHECK: Inco Lightning program
+    #[account(address = INCO_LIGHTNING_ID)]
+    pub inco_lightning_program: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn initialize_campaign<'info>(
+    ctx: Context<'_, '_, '_, 'info, InitializeCampaign<'info>>,
+    campaign_id: [u8; 32],
+    eligibility_root: [u8; 32],
+    zk_verifier_program: Pubkey,
+    pool_options: VotingPoolOptions,
+    winners_deadline: i64,
+    dispute_window_seconds: i64,
+) -> Result<()> {
+    validate_pool_options(&pool_options)?;
+    // Openers prove with a single-question circuit, as delegators do.
+    require!(
+        pool_options.question_count <= 1 && dispute_window_seconds >= 0,
+        ErrorCode::InvalidPoolOptions
+    );
+    // The dispute pool must come from `open_dispute`, so it can't exist yet.
+    require!(
+        ctx.accounts.voting_pool.data_is_empty(),
+        ErrorCode::DisputeUnavailable
+    );
+
+    let campaign = &mut ctx.accounts.campaign;
+    campaign.version = Campaign::VERSION;
+    campaign.campaign_id = campaign_id;
+    campaign.authority = ctx.accounts.authority.key();
+    campaign.eligibility_root = eligibility_root;
+    campaign.zk_verifier_program = zk_verifier_program;
+    campaign.pool_options = pool_options;
+    campaign.winners_deadline = winners_deadline;
+    campaign.dispute_window_seconds = dispute_window_seconds;
+    campaign.winners_selected = false;
+    campaign.dispute_opened = false;
+    campaign.winners_root = [0u8; 32];
+    campaign.winner_count = 0;
+
+    emit!(CampaignInitialized {
+        schema_version: EVENT_SCHEMA_VERSION,
+        campaign_id,
+        campaign: campaign.key(),
+        authority: campaign.authority,
+        eligibility_root,
+        eligible_count: campaign.pool_options.eligible_count,
+        winners_deadline,
+        dispute_window_seconds,
+    });
+
+    Ok(())
+}
+
+/// Replaces the eligibility set, e.g. after recipients are added. Frozen at the
+/// winners deadline so the set a dispute opens with can't be changed under it.
+pub(crate) fn set_campaign_eligibility<'info>(
+    ctx: Context<'_, '_, '_, 'info, UpdateCampaign<'info>>,
+    campaign_id: [u8; 32],
+    eligibility_root: [u8; 32],
+    eligible_count: u64,
+) -> Result<()> {
+    let campaign = &mut ctx.accounts.campaign;
+    require!(
+        Clock::get()?.unix_timestamp <= campaign.winners_deadline,
+        ErrorCode::WinnersDeadlinePassed
+    );
+    campaign.eligibility_root = eligibility_root;
+    campaign.pool_options.eligible_count = eligible_count;
+
+    emit!(CampaignEligibilityUpdated {
+        schema_version: EVENT_SCHEMA_VERSION,
+        campaign_id,
+        campaign: campaign.key(),
+        eligibility_root,
+        eligible_count,
+    });
+
+    Ok(())
+}
+
+/// Commits the winners as a Merkle root over their identity leaves, before the
+/// winners deadline. The root is set once, so the host can't rewrite the
+/// winners after announcing them.
+///
+/// With an escrow, the root becomes its claim root and each winner is paid by
+/// `claim_zk` against it.
+pub(crate) fn commit_winners<'info>(
+    ctx: Context<'_, '_, '_, 'info, CommitWinners<'info>>,
+    campaign_id: [u8; 32],
+    winners_root: [u8; 32],
+    winner_count: u32,
+) -> Result<()> {
+    let campaign = &mut ctx.accounts.campaign;
+    require!(
+        Clock::get()?.unix_timestamp <= campaign.winners_deadline,
+        ErrorCode::WinnersDeadlinePassed
+    );
+    require!(
+        campaign.winners_root == [0u8; 32] && !campaign.dispute_opened,
+        ErrorCode::WinnersAlreadyCommitted
+    );
+    require!(
+        winners_root != [0u8; 32] && winner_count > 0,
+        ErrorCode::InvalidWinners
+    );
+    campaign.winners_root = winners_root;
+    campaign.winner_count = winner_count;
+    campaign.winners_selected = true;
+
+    // The escrow address is fixed by seeds, so an existing escrow can't be
+    // left out and miss the root.
+    let info = ctx.accounts.escrow.to_account_info();
+    let escrow = match read_escrow(&info)? {
+        Some(mut escrow) => {
+            require!(
+                !escrow.is_closed && winner_count <= escrow.max_claims,
+                ErrorCode::InvalidWinners
+            );
+            escrow.claim_root = winners_root;
+            migration::write_account(&info, &escrow)?;
+            info.key()
+        }
+        None => Pubkey::default(),
+    };
+
+    emit!(WinnersCommitted {
+        schema_version: EVENT_SCHEMA_VERSION,
+        campaign_id,
+        campaign: campaign.key(),
+        winners_root,
+        winner_count,
+        escrow,
+    });
+
+    Ok(())
+}
+
+/// Opens the campaign's dispute once the winners deadline has passed without a
+/// selection. Any eligible recipient may call it, as may the campaign
+/// authority, which needs no proof.
+///
+/// The proof is a vote-circuit proof committing to the campaign id instead of
+/// a ballot, with its nullifier in the circuit's dispute domain. The nullifier
+/// is checked but not spent, so the opener can still vote.
+pub(crate) fn open_dispute<'info>(
+    ctx: Context<'_, '_, '_, 'info, OpenDispute<'info>>,
+    campaign_id: [u8; 32],
+    nullifier_value: [u8; 32],
+    proof: Vec<u8>,
+    public_witness: Vec<u8>,
+) -> Result<()> {
+    let campaign = &ctx.accounts.campaign;
+    require!(
+        !campaign.winners_selected && !campaign.dispute_opened,
+        ErrorCode::DisputeUnavailable
+    );
+    let now = Clock::get()?.unix_timestamp;
+    require!(now > campaign.winners_deadline, ErrorCode::WinnersDeadlineNotPassed);
+
+    let inco = ctx.accounts.inco_lightning_program.to_account_info();
+    let signer = ctx.accounts.opener.to_account_info();
+    setup_voting_pool(
+        &mut ctx.accounts.voting_pool,
+        &inco,
+        &signer,
+        campaign.authority,
+        campaign_id,
+        campaign.eligibility_root,
+        campaign.zk_verifier_program,
+        &campaign.pool_options,
+    )?;
+
+    if ctx.accounts.opener.key() != campaign.authority {
+        verify_ballot(
+            &ctx.accounts.voting_pool,
+            &ctx.accounts.zk_verifier_program,
+            &nullifier_value,
+            &proof,
+            &public_witness,
+            &dispute_commitment(&campaign_id)?,
+        )?;
+    }
+
+    let voting_ends_at = if campaign.dispute_window_seconds > 0 {
+        now + campaign.dispute_window_seconds
+    } else {
+        0
+    };
+    let voting_pool = &mut ctx.accounts.voting_pool;
+    voting_pool.voting_ends_at = voting_ends_at;
+    ctx.accounts.campaign.dispute_opened = true;
+
+    emit!(DisputeOpened {
+        schema_version: EVENT_SCHEMA_VERSION,
+        campaign_id,
+        campaign: ctx.accounts.campaign.key(),
+        voting_pool: voting_pool.key(),
+        opener: ctx.accounts.opener.key(),
+        voting_ends_at,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,300 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::ErrorCode;
+use crate::escrow::{read_escrow, release_funds, Escrow, UpdateEscrow};
+use crate::events::{ClaimPaid, ClaimRecorded, ClaimRootSet, EVENT_SCHEMA_VERSION};
+use crate::voting::{
+    invoke_verifier, poseidon_hash_bytes, public_witness_len, witness_field, ZK_PROOF_LEN,
+};
+
+// Public inputs of `claim_eligibility`: merkle_root, nullifier, recipient.
+const CLAIM_PUBLIC_INPUTS: usize = 3;
+
+/// The deployed `claim_eligibility` verifier, set at build time through
+/// `CLAIM_VERIFIER_PROGRAM_ID`. Without it `claim_zk` is unavailable.
+pub const CLAIM_VERIFIER_PROGRAM_ID: Pubkey = match option_env!("CLAIM_VERIFIER_PROGRAM_ID") {
+    Some(id) => Pubkey::from_str_const(id),
+    None => Pubkey::new_from_array([0u8; 32]),
+};
+
+/// A spent claim; its address blocks a second claim with the same nullifier.
+#[account]
+#[derive(InitSpace)]
+pub struct ClaimNullifier {
+    pub campaign_id: [u8; 32],
+    pub value: [u8; 32],
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub claimed_at: i64,
+}
+
+/// Claims an authority paid off-chain, counted against the campaign's limit.
+///
+/// Each authority keeps its own log per campaign, so opening one can't
+/// pre-empt the campaign host's.
+#[account]
+#[derive(InitSpace)]
+pub struct ClaimLog {
+    pub campaign_id: [u8; 32],
+    pub authority: Pubkey,
+    pub max_claims: u32,
+    pub claim_count: u32,
+    /// sha256 chain over the recorded nullifiers, so records can't be dropped
+    /// or reordered without changing it.
+    pub log_hash: [u8; 32],
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct ClaimRecord {
+    pub campaign_id: [u8; 32],
+    pub nullifier: [u8; 32],
+    /// Position in the campaign's claim log.
+    pub index: u32,
+    pub recorded_at: i64,
+}
+
+#[derive(Accounts)]
+#[instruction(campaign_id: [u8; 32], nullifier_value: [u8; 32])]
+pub struct ClaimZk<'info> {
+    #[account(
+        mut,
+        seeds = [b"escrow", campaign_id.as_ref()],
+        bump
+    )]
+    pub escrow: Account<'info, Escrow>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + ClaimNullifier::INIT_SPACE,
+        seeds = [b"claim_nullifier", campaign_id.as_ref(), nullifier_value.as_ref()],
+        bump
+    )]
+    pub claim_nullifier: Account<'info, ClaimNullifier>,
+    /// CHECK: Wallet the proof binds the payout to
+    #[account(mut)]
+    pub recipient: AccountInfo<'info>,
+    /// The recipient's token account, for SPL escrows.
+    #[account(
+        mut,
+        constraint = recipient_token_account.owner == recipient.key() @ ErrorCode::Unauthorized
+    )]
+    pub recipient_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    /// Relayer paying for the nullifier account, so the claimant's wallet stays unlinked.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// CHECK: Claim verifier program
+    #[account(address = CLAIM_VERIFIER_PROGRAM_ID @ ErrorCode::InvalidZkVerifier)]
+    pub zk_verifier_program: AccountInfo<'info>,
+    #[account(mut, address = escrow.vault @ ErrorCode::InvalidEscrowVault)]
+    pub vault: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(address = escrow.mint @ ErrorCode::InvalidEscrowVault)]
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+    #[account(address = escrow.token_program @ ErrorCode::InvalidEscrowVault)]
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(campaign_id: [u8; 32], nullifier: [u8; 32])]
+pub struct RecordClaim<'info> {
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + ClaimLog::INIT_SPACE,
+        seeds = [b"claim_log", campaign_id.as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub claim_log: Account<'info, ClaimLog>,
+    /// CHECK: The campaign's escrow address, which need not hold an escrow
+    #[account(
+        seeds = [b"escrow", campaign_id.as_ref()],
+        bump
+    )]
+    pub escrow: UncheckedAccount<'info>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + ClaimRecord::INIT_SPACE,
+        seeds = [b"claim_record", campaign_id.as_ref(), nullifier.as_ref()],
+        bump
+    )]
+    pub claim_record: Account<'info, ClaimRecord>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Sets the Merkle root claimants prove membership in.
+///
+/// Fixed once the first claim is paid, so paid claimants can't be swapped out.
+/// Escrows with a winners deadline take their root from `commit_winners` instead.
+pub(crate) fn set_claim_root<'info>(
+    ctx: Context<'_, '_, '_, 'info, UpdateEscrow<'info>>,
+    campaign_id: [u8; 32],
+    claim_root: [u8; 32],
+) -> Result<()> {
+    let escrow = &mut ctx.accounts.escrow;
+    require!(!escrow.is_closed, ErrorCode::EscrowClosed);
+    require!(
+        escrow.claim_count == 0
+            && escrow.winners_deadline == 0
+            && !escrow.equal_distribution_settled,
+        ErrorCode::ClaimUnavailable
+    );
+    escrow.claim_root = claim_root;
+
+    emit!(ClaimRootSet {
+        schema_version: EVENT_SCHEMA_VERSION,
+        campaign_id,
+        escrow: escrow.key(),
+        claim_root,
+        claim_verifier_program: escrow.claim_verifier_program,
+    });
+
+    Ok(())
+}
+
+/// Pays `payout_amount` from the vault to `recipient` for a `claim_eligibility`
+/// proof against the escrow's claim root.
+///
+/// The proof binds the recipient wallet, so a relayer can submit it without
+/// being able to redirect the payout. Each nullifier claims once.
+pub(crate) fn claim_zk<'info>(
+    ctx: Context<'_, '_, '_, 'info, ClaimZk<'info>>,
+    campaign_id: [u8; 32],
+    nullifier_value: [u8; 32],
+    proof: Vec<u8>,
+    public_witness: Vec<u8>,
+) -> Result<()> {
+    let escrow = &ctx.accounts.escrow;
+    require!(!escrow.is_closed, ErrorCode::EscrowClosed);
+    require!(
+        escrow.claim_root != [0u8; 32] && CLAIM_VERIFIER_PROGRAM_ID != Pubkey::default(),
+        ErrorCode::ClaimUnavailable
+    );
+    let claimed_at = Clock::get()?.unix_timestamp;
+    require!(claimed_at <= escrow.expires_at, ErrorCode::ClaimUnavailable);
+    require!(escrow.claim_count < escrow.max_claims, ErrorCode::ClaimLimitReached);
+
+    require!(proof.len() == ZK_PROOF_LEN, ErrorCode::InvalidProofLength);
+    require!(
+        public_witness.len() == public_witness_len(CLAIM_PUBLIC_INPUTS),
+        ErrorCode::InvalidPublicWitnessLength
+    );
+    require!(
+        witness_field(&public_witness, 0) == escrow.claim_root.as_ref(),
+        ErrorCode::MerkleRootMismatch
+    );
+    require!(
+        witness_field(&public_witness, 1) == nullifier_value.as_ref(),
+        ErrorCode::NullifierMismatch
+    );
+    let recipient = ctx.accounts.recipient.key();
+    require!(
+        witness_field(&public_witness, 2) == poseidon_hash_bytes(recipient.as_ref())?.as_ref(),
+        ErrorCode::RecipientMismatch
+    );
+    invoke_verifier(&ctx.accounts.zk_verifier_program, &proof, &public_witness)?;
+
+    let accounts = &ctx.accounts;
+    let tokens = accounts
+        .escrow
+        .tokens(&accounts.vault, &accounts.mint, &accounts.token_program)?;
+    let destination = match &tokens {
+        Some(_) => accounts
+            .recipient_token_account
+            .as_ref()
+            .ok_or(ErrorCode::InvalidEscrowVault)?
+            .to_account_info(),
+        None => accounts.recipient.clone(),
+    };
+    let amount = accounts.escrow.payout_amount;
+    release_funds(
+        &accounts.escrow,
+        ctx.bumps.escrow,
+        tokens.as_ref(),
+        &destination,
+        amount,
+    )?;
+
+    let escrow = &mut ctx.accounts.escrow;
+    escrow.claim_count += 1;
+    escrow.released = escrow.released.saturating_add(amount);
+
+    let claim_nullifier = &mut ctx.accounts.claim_nullifier;
+    claim_nullifier.campaign_id = campaign_id;
+    claim_nullifier.value = nullifier_value;
+    claim_nullifier.recipient = recipient;
+    claim_nullifier.amount = amount;
+    claim_nullifier.claimed_at = claimed_at;
+
+    emit!(ClaimPaid {
+        schema_version: EVENT_SCHEMA_VERSION,
+        campaign_id,
+        escrow: escrow.key(),
+        nullifier: nullifier_value,
+        recipient,
+        amount,
+        claim_count: escrow.claim_count,
+    });
+
+    Ok(())
+}
+
+/// Records a claim the server is about to pay, before it pays it.
+///
+/// `nullifier` is `sha256("claim" || campaign_id || identity_hash)`, computed
+/// off-chain so the identity hash stays private; each records once.
+///
+/// For a campaign with an escrow only its authority may record, and the limit
+/// is the escrow's `max_claims`. Otherwise the first record opens the signer's
+/// log with `max_claims`, and later records must repeat the same limit.
+pub(crate) fn record_claim<'info>(
+    ctx: Context<'_, '_, '_, 'info, RecordClaim<'info>>,
+    campaign_id: [u8; 32],
+    nullifier: [u8; 32],
+    max_claims: u32,
+) -> Result<()> {
+    let authority = ctx.accounts.authority.key();
+    let max_claims = match read_escrow(&ctx.accounts.escrow)? {
+        Some(escrow) => {
+            require_keys_eq!(escrow.authority, authority, ErrorCode::Unauthorized);
+            escrow.max_claims
+        }
+        None => max_claims,
+    };
+    let claim_log = &mut ctx.accounts.claim_log;
+    if claim_log.authority == Pubkey::default() {
+        require!(max_claims > 0, ErrorCode::ClaimUnavailable);
+        claim_log.campaign_id = campaign_id;
+        claim_log.authority = authority;
+        claim_log.max_claims = max_claims;
+    }
+    require!(claim_log.max_claims == max_claims, ErrorCode::ClaimUnavailable);
+    require!(claim_log.claim_count < claim_log.max_claims, ErrorCode::ClaimLimitReached);
+
+    let index = claim_log.claim_count;
+    claim_log.claim_count += 1;
+    claim_log.log_hash = hashv(&[claim_log.log_hash.as_ref(), nullifier.as_ref()]).to_bytes();
+
+    let recorded_at = Clock::get()?.unix_timestamp;
+    let claim_record = &mut ctx.accounts.claim_record;
+    claim_record.campaign_id = campaign_id;
+    claim_record.nullifier = nullifier;
+    claim_record.index = index;
+    claim_record.recorded_at = recorded_at;
+
+    emit!(ClaimRecorded {
+        schema_version: EVENT_SCHEMA_VERSION,
+        campaign_id,
+        claim_log: claim_log.key(),
+        nullifier,
+        index,
+        log_hash: claim_log.log_hash,
+        recorded_at,
+    });
+
+    Ok(())
+}
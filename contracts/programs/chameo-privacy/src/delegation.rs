@@ -0,0 +1,133 @@
+use anchor_lang::prelude::*;
+use inco_lightning::cpi::accounts::Operation;
+use inco_lightning::cpi::{as_euint128, e_add};
+use inco_lightning::ID as INCO_LIGHTNING_ID;
+use crate::ErrorCode;
+use crate::events::{VoteDelegated, EVENT_SCHEMA_VERSION};
+use crate::handle::IncoHandle;
+use crate::voting::{delegation_commitment, verify_ballot, Nullifier, VotingPool};
+
+/// Weight delegated to the voter behind `delegate`, applied when they cast.
+///
+/// The delegate's nullifier account only exists once they have cast, so the
+/// weight is applied at most once and can't grow after that.
+#[account]
+#[derive(InitSpace)]
+pub struct Delegation {
+    pub campaign_id: [u8; 32],
+    pub delegate: [u8; 32],
+    pub weight: IncoHandle,
+}
+
+#[derive(Accounts)]
+#[instruction(campaign_id: [u8; 32], nullifier_value: [u8; 32], delegate_nullifier: [u8; 32])]
+pub struct DelegateVote<'info> {
+    #[account(
+        init,
+        payer = relayer,
+        space = 8 + Nullifier::INIT_SPACE,
+        seeds = [b"nullifier", campaign_id.as_ref(), nullifier_value.as_ref()],
+        bump
+    )]
+    pub nullifier: Account<'info, Nullifier>,
+    #[account(
+        init_if_needed,
+        payer = relayer,
+        space = 8 + Delegation::INIT_SPACE,
+        seeds = [b"delegation", campaign_id.as_ref(), delegate_nullifier.as_ref()],
+        bump
+    )]
+    pub delegation: Account<'info, Delegation>,
+    /// CHECK: Must be empty; the delegate can't have voted yet
+    #[account(
+        seeds = [b"nullifier", campaign_id.as_ref(), delegate_nullifier.as_ref()],
+        bump
+    )]
+    pub delegate_nullifier_account: UncheckedAccount<'info>,
+    /// CHECK: Must be empty; weight delegated to the delegator can't be passed on
+    #[account(
+        seeds = [b"delegation", campaign_id.as_ref(), nullifier_value.as_ref()],
+        bump
+    )]
+    pub delegator_delegation: UncheckedAccount<'info>,
+    #[account(
+        seeds = [b"voting_pool", campaign_id.as_ref()],
+        bump
+    )]
+    pub voting_pool: Account<'info, VotingPool>,
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+    /// CHECK: ZK verifier program
+    pub zk_verifier_program: AccountInfo<'info>,
+    /// CHECK: Inco Lightning program
+    #[account(address = INCO_LIGHTNING_ID)]
+    pub inco_lightning_program: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn delegate_vote<'info>(
+    ctx: Context<'_, '_, '_, 'info, DelegateVote<'info>>,
+    campaign_id: [u8; 32],
+    nullifier_value: [u8; 32],
+    delegate_nullifier: [u8; 32],
+    proof: Vec<u8>,
+    public_witness: Vec<u8>,
+) -> Result<()> {
+    let voting_pool = &ctx.accounts.voting_pool;
+    // A replaced ballot couldn't carry delegated weight forward, so revote pools
+    // opt out, and multi-question pools verify with a circuit that has no
+    // delegation form.
+    require!(
+        !voting_pool.allow_revote && voting_pool.question_count <= 1,
+        ErrorCode::DelegationUnavailable
+    );
+    require!(!voting_pool.jury_pending, ErrorCode::JuryDrawPending);
+    require!(nullifier_value != delegate_nullifier, ErrorCode::InvalidDelegation);
+    require!(
+        ctx.accounts.delegate_nullifier_account.data_is_empty(),
+        ErrorCode::InvalidDelegation
+    );
+    require!(
+        ctx.accounts.delegator_delegation.data_is_empty(),
+        ErrorCode::InvalidDelegation
+    );
+
+    // The proof commits to the delegate instead of a ciphertext.
+    let weight = verify_ballot(
+        voting_pool,
+        &ctx.accounts.zk_verifier_program,
+        &nullifier_value,
+        &proof,
+        &public_witness,
+        &delegation_commitment(&delegate_nullifier)?,
+    )?;
+
+    let nullifier_account = &mut ctx.accounts.nullifier;
+    nullifier_account.campaign_id = campaign_id;
+    nullifier_account.value = nullifier_value;
+
+    let inco = ctx.accounts.inco_lightning_program.to_account_info();
+    let signer = ctx.accounts.relayer.to_account_info();
+    let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let delegated = as_euint128(cpi_ctx, weight as u128)?;
+
+    let delegation = &mut ctx.accounts.delegation;
+    delegation.weight = if delegation.weight == IncoHandle::default() {
+        delegation.campaign_id = campaign_id;
+        delegation.delegate = delegate_nullifier;
+        delegated.into()
+    } else {
+        let cpi_ctx = CpiContext::new(inco, Operation { signer });
+        e_add(cpi_ctx, delegation.weight.into(), delegated, 0)?.into()
+    };
+
+    emit!(VoteDelegated {
+        schema_version: EVENT_SCHEMA_VERSION,
+        campaign_id,
+        voting_pool: voting_pool.key(),
+        nullifier: nullifier_value,
+        delegate: delegate_nullifier,
+    });
+
+    Ok(())
+}
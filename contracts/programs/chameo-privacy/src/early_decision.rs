@@ -0,0 +1,200 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar::instructions::ID as INSTRUCTIONS_SYSVAR_ID;
+use inco_lightning::cpi::accounts::{Allow, Operation};
+use inco_lightning::cpi::{allow, as_euint128, e_add, e_gt, e_select, e_sub};
+use inco_lightning::ID as INCO_LIGHTNING_ID;
+use crate::ErrorCode;
+use crate::attestation;
+use crate::events::{EarlyDecisionChecked, EarlyDecisionConfirmed, EVENT_SCHEMA_VERSION};
+use crate::voting::{BallotType, VotingPool};
+
+#[derive(Accounts)]
+#[instruction(campaign_id: [u8; 32])]
+pub struct CheckEarlyDecision<'info> {
+    #[account(
+        mut,
+        seeds = [b"voting_pool", campaign_id.as_ref()],
+        bump
+    )]
+    pub voting_pool: Account<'info, VotingPool>,
+    pub requester: Signer<'info>,
+    /// CHECK: Inco Lightning program
+    #[account(address = INCO_LIGHTNING_ID)]
+    pub inco_lightning_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(campaign_id: [u8; 32])]
+pub struct GrantEarlyDecisionAccess<'info> {
+    #[account(
+        seeds = [b"voting_pool", campaign_id.as_ref()],
+        bump
+    )]
+    pub voting_pool: Account<'info, VotingPool>,
+    #[account(mut)]
+    pub requester: Signer<'info>,
+    /// CHECK: Allowance account for the early decision handle
+    #[account(mut)]
+    pub allowance_decision: AccountInfo<'info>,
+    /// CHECK: Inco Lightning program
+    #[account(address = INCO_LIGHTNING_ID)]
+    pub inco_lightning_program: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(campaign_id: [u8; 32])]
+pub struct ConfirmEarlyDecision<'info> {
+    #[account(
+        mut,
+        seeds = [b"voting_pool", campaign_id.as_ref()],
+        bump
+    )]
+    pub voting_pool: Account<'info, VotingPool>,
+    #[account(mut)]
+    pub requester: Signer<'info>,
+    /// CHECK: Instructions sysvar holding the attested-decrypt signature
+    #[account(address = INSTRUCTIONS_SYSVAR_ID)]
+    pub instructions: AccountInfo<'info>,
+    /// CHECK: Inco Lightning program
+    #[account(address = INCO_LIGHTNING_ID)]
+    pub inco_lightning_program: AccountInfo<'info>,
+}
+
+/// Encrypts whether either side leads by more than the weight still uncast.
+///
+/// Once true this stays true: with no revotes, every later ballot moves weight
+/// from the remainder to one side, which can't close the gap.
+pub(crate) fn check_early_decision<'info>(
+    ctx: Context<'_, '_, '_, 'info, CheckEarlyDecision<'info>>,
+    campaign_id: [u8; 32],
+) -> Result<()> {
+    let voting_pool = &mut ctx.accounts.voting_pool;
+    require!(voting_pool.is_active, ErrorCode::VotingNotActive);
+    require!(!voting_pool.decided_early, ErrorCode::EarlyDecisionUnavailable);
+    require!(voting_pool.eligible_count > 0, ErrorCode::EarlyDecisionUnavailable);
+    // Other ballot types have no single leader, and revotes can swing a decided pool.
+    // Only the first question is checked, so later questions must not exist.
+    require!(
+        voting_pool.ballot_type == BallotType::SingleChoice
+            && !voting_pool.allow_revote
+            && voting_pool.question_count <= 1,
+        ErrorCode::EarlyDecisionUnavailable
+    );
+    // Below the anonymity threshold the decision would reveal how the few voters voted.
+    require!(
+        voting_pool.total_votes >= voting_pool.min_votes,
+        ErrorCode::VotingVoid
+    );
+    // Encrypted turnout counts ballots, not weight.
+    require!(
+        !(voting_pool.encrypt_turnout && voting_pool.weighted),
+        ErrorCode::EarlyDecisionUnavailable
+    );
+
+    let inco = ctx.accounts.inco_lightning_program.to_account_info();
+    let signer = ctx.accounts.requester.to_account_info();
+
+    let remaining = if voting_pool.encrypt_turnout {
+        let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+        let eligible = as_euint128(cpi_ctx, voting_pool.eligible_count as u128)?;
+        let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+        e_sub(cpi_ctx, eligible, voting_pool.encrypted_total_votes.into(), 0)?
+    } else {
+        let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+        as_euint128(
+            cpi_ctx,
+            voting_pool.eligible_count.saturating_sub(voting_pool.cast_weight) as u128,
+        )?
+    };
+
+    let refund = voting_pool.refund_host_votes.into();
+    let equal = voting_pool.equal_distribution_votes.into();
+
+    let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let equal_ceiling = e_add(cpi_ctx, equal, remaining, 0)?;
+    let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let refund_decided = e_gt(cpi_ctx, refund, equal_ceiling, 0)?;
+
+    let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let refund_ceiling = e_add(cpi_ctx, refund, remaining, 0)?;
+    let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let equal_decided = e_gt(cpi_ctx, equal, refund_ceiling, 0)?;
+
+    let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let one = as_euint128(cpi_ctx, 1)?;
+    let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let zero = as_euint128(cpi_ctx, 0)?;
+
+    let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let either = e_select(cpi_ctx, equal_decided, one, zero, 0)?;
+    let cpi_ctx = CpiContext::new(inco, Operation { signer });
+    voting_pool.early_decision = e_select(cpi_ctx, refund_decided, one, either, 0)?.into();
+
+    emit!(EarlyDecisionChecked {
+        schema_version: EVENT_SCHEMA_VERSION,
+        campaign_id,
+        voting_pool: voting_pool.key(),
+        requester: ctx.accounts.requester.key(),
+    });
+
+    Ok(())
+}
+
+/// Lets the requester decrypt the latest early decision handle. It reveals only
+/// whether the vote is settled, not who leads.
+pub(crate) fn grant_early_decision_access<'info>(
+    ctx: Context<'_, '_, '_, 'info, GrantEarlyDecisionAccess<'info>>,
+    _campaign_id: [u8; 32],
+) -> Result<()> {
+    let voting_pool = &ctx.accounts.voting_pool;
+    require!(
+        voting_pool.early_decision.0 != 0,
+        ErrorCode::EarlyDecisionUnavailable
+    );
+
+    let requester = ctx.accounts.requester.to_account_info();
+    let cpi_ctx = CpiContext::new(
+        ctx.accounts.inco_lightning_program.to_account_info(),
+        Allow {
+            allowance_account: ctx.accounts.allowance_decision.to_account_info(),
+            signer: requester.clone(),
+            allowed_address: requester.clone(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+        },
+    );
+    allow(cpi_ctx, voting_pool.early_decision.0, true, requester.key())?;
+
+    Ok(())
+}
+
+/// Records an early decision once its handle is attested to decrypt to 1, so
+/// the authority may close before the window ends.
+pub(crate) fn confirm_early_decision<'info>(
+    ctx: Context<'_, '_, '_, 'info, ConfirmEarlyDecision<'info>>,
+    campaign_id: [u8; 32],
+) -> Result<()> {
+    let voting_pool = &mut ctx.accounts.voting_pool;
+    require!(voting_pool.is_active, ErrorCode::VotingNotActive);
+    require!(
+        voting_pool.early_decision.0 != 0,
+        ErrorCode::EarlyDecisionUnavailable
+    );
+
+    attestation::verify_decryption(
+        &ctx.accounts.inco_lightning_program.to_account_info(),
+        &ctx.accounts.instructions.to_account_info(),
+        &ctx.accounts.requester.to_account_info(),
+        voting_pool.early_decision,
+        1,
+    )?;
+    voting_pool.decided_early = true;
+
+    emit!(EarlyDecisionConfirmed {
+        schema_version: EVENT_SCHEMA_VERSION,
+        campaign_id,
+        voting_pool: voting_pool.key(),
+    });
+
+    Ok(())
+}
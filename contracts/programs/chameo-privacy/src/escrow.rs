@@ -0,0 +1,533 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{
+    transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+use crate::ErrorCode;
+use crate::appeal::round_campaign_id;
+use crate::claim::CLAIM_VERIFIER_PROGRAM_ID;
+use crate::events::{EscrowFunded, EscrowInitialized, EscrowRefunded, EVENT_SCHEMA_VERSION};
+use crate::voting::{VoteOutcome, VotingPool};
+
+/// Funds and payout terms of an escrow campaign.
+///
+/// SOL escrows are their own vault: deposits are lamports held above rent. SPL
+/// escrows hold funds in an associated token account owned by this PDA. Either
+/// way only this program's release rules can move them out.
+// New fields must be appended, as with `VotingPool`.
+#[account]
+#[derive(InitSpace)]
+pub struct Escrow {
+    pub version: u8,
+    pub campaign_id: [u8; 32],
+    pub authority: Pubkey,
+    /// Where `refund_escrow` sends the balance; fixed at creation.
+    pub refund_address: Pubkey,
+    /// Paid per claim, in lamports or the mint's base units.
+    pub payout_amount: u64,
+    pub max_claims: u32,
+    /// Unix time after which the balance can be refunded.
+    pub expires_at: i64,
+    /// Unix time by which the host must select winners; 0 for none.
+    pub winners_deadline: i64,
+    pub deposited: u64,
+    pub released: u64,
+    pub is_closed: bool,
+    /// SPL Token or Token-2022 mint paid out; default for SOL.
+    pub mint: Pubkey,
+    /// Decimals of `mint`, so amounts are read in base units; 9 for SOL.
+    pub decimals: u8,
+    pub token_program: Pubkey,
+    pub vault: Pubkey,
+    /// Root `claim_zk` proofs are checked against; zero until set.
+    pub claim_root: [u8; 32],
+    /// `claim_eligibility` verifier this program was built with, recorded at creation.
+    pub claim_verifier_program: Pubkey,
+    pub claim_count: u32,
+    /// Whether `settle_equal_distribution` split the balance into per-leaf shares.
+    pub equal_distribution_settled: bool,
+}
+
+impl Escrow {
+    pub const VERSION: u8 = 1;
+
+    pub fn is_token(&self) -> bool {
+        self.mint != Pubkey::default()
+    }
+
+    /// The token accounts passed for an SPL escrow; `None` for SOL.
+    ///
+    /// Callers constrain each account to the addresses stored here.
+    pub(crate) fn tokens<'a, 'info>(
+        &self,
+        vault: &'a Option<InterfaceAccount<'info, TokenAccount>>,
+        mint: &'a Option<InterfaceAccount<'info, Mint>>,
+        token_program: &'a Option<Interface<'info, TokenInterface>>,
+    ) -> Result<Option<EscrowTokens<'a, 'info>>> {
+        if !self.is_token() {
+            return Ok(None);
+        }
+        match (vault, mint, token_program) {
+            (Some(vault), Some(mint), Some(token_program)) => Ok(Some(EscrowTokens {
+                vault,
+                mint,
+                token_program,
+            })),
+            _ => err!(ErrorCode::InvalidEscrowVault),
+        }
+    }
+}
+
+pub(crate) struct EscrowTokens<'a, 'info> {
+    pub vault: &'a InterfaceAccount<'info, TokenAccount>,
+    pub mint: &'a InterfaceAccount<'info, Mint>,
+    pub token_program: &'a Interface<'info, TokenInterface>,
+}
+
+/// Decimals recorded for SOL escrows.
+const LAMPORT_DECIMALS: u8 = 9;
+
+#[derive(Accounts)]
+#[instruction(campaign_id: [u8; 32])]
+pub struct InitializeEscrow<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Escrow::INIT_SPACE,
+        seeds = [b"escrow", campaign_id.as_ref()],
+        bump
+    )]
+    pub escrow: Account<'info, Escrow>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(campaign_id: [u8; 32])]
+pub struct InitializeTokenEscrow<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Escrow::INIT_SPACE,
+        seeds = [b"escrow", campaign_id.as_ref()],
+        bump
+    )]
+    pub escrow: Account<'info, Escrow>,
+    #[account(mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        init,
+        payer = authority,
+        associated_token::mint = mint,
+        associated_token::authority = escrow,
+        associated_token::token_program = token_program
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(campaign_id: [u8; 32])]
+pub struct UpdateEscrow<'info> {
+    #[account(
+        mut,
+        seeds = [b"escrow", campaign_id.as_ref()],
+        bump,
+        constraint = escrow.authority == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub escrow: Account<'info, Escrow>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(campaign_id: [u8; 32])]
+pub struct FundEscrow<'info> {
+    #[account(
+        mut,
+        seeds = [b"escrow", campaign_id.as_ref()],
+        bump
+    )]
+    pub escrow: Account<'info, Escrow>,
+    #[account(mut)]
+    pub funder: Signer<'info>,
+    /// Source of the deposit for SPL escrows.
+    #[account(mut)]
+    pub funder_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut, address = escrow.vault @ ErrorCode::InvalidEscrowVault)]
+    pub vault: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(address = escrow.mint @ ErrorCode::InvalidEscrowVault)]
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+    #[account(address = escrow.token_program @ ErrorCode::InvalidEscrowVault)]
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(campaign_id: [u8; 32])]
+pub struct RefundEscrow<'info> {
+    #[account(
+        mut,
+        seeds = [b"escrow", campaign_id.as_ref()],
+        bump
+    )]
+    pub escrow: Account<'info, Escrow>,
+    /// CHECK: Refund destination recorded on the escrow
+    #[account(
+        mut,
+        address = escrow.refund_address @ ErrorCode::Unauthorized
+    )]
+    pub refund_address: AccountInfo<'info>,
+    /// The refund address's token account, for SPL escrows.
+    #[account(
+        mut,
+        constraint = refund_token_account.owner == escrow.refund_address @ ErrorCode::Unauthorized
+    )]
+    pub refund_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    /// Final round of the campaign's dispute, when refunding on its outcome.
+    pub voting_pool: Option<Account<'info, VotingPool>>,
+    pub requester: Signer<'info>,
+    #[account(mut, address = escrow.vault @ ErrorCode::InvalidEscrowVault)]
+    pub vault: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(address = escrow.mint @ ErrorCode::InvalidEscrowVault)]
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+    #[account(address = escrow.token_program @ ErrorCode::InvalidEscrowVault)]
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+}
+
+/// Opens a SOL escrow; payouts are in lamports.
+pub(crate) fn initialize_escrow<'info>(
+    ctx: Context<'_, '_, '_, 'info, InitializeEscrow<'info>>,
+    campaign_id: [u8; 32],
+    refund_address: Pubkey,
+    payout_amount: u64,
+    max_claims: u32,
+    expires_at: i64,
+    winners_deadline: i64,
+) -> Result<()> {
+    let authority = ctx.accounts.authority.key();
+    let escrow = &mut ctx.accounts.escrow;
+    escrow.mint = Pubkey::default();
+    escrow.decimals = LAMPORT_DECIMALS;
+    escrow.token_program = Pubkey::default();
+    escrow.vault = escrow.key();
+    set_escrow_terms(
+        escrow,
+        campaign_id,
+        authority,
+        refund_address,
+        payout_amount,
+        max_claims,
+        expires_at,
+        winners_deadline,
+    )
+}
+
+/// Opens an escrow paying out an SPL Token or Token-2022 mint, in its base
+/// units. The vault is the escrow's associated token account for the mint.
+pub(crate) fn initialize_token_escrow<'info>(
+    ctx: Context<'_, '_, '_, 'info, InitializeTokenEscrow<'info>>,
+    campaign_id: [u8; 32],
+    refund_address: Pubkey,
+    payout_amount: u64,
+    max_claims: u32,
+    expires_at: i64,
+    winners_deadline: i64,
+) -> Result<()> {
+    let authority = ctx.accounts.authority.key();
+    let escrow = &mut ctx.accounts.escrow;
+    escrow.mint = ctx.accounts.mint.key();
+    escrow.decimals = ctx.accounts.mint.decimals;
+    escrow.token_program = ctx.accounts.token_program.key();
+    escrow.vault = ctx.accounts.vault.key();
+    set_escrow_terms(
+        escrow,
+        campaign_id,
+        authority,
+        refund_address,
+        payout_amount,
+        max_claims,
+        expires_at,
+        winners_deadline,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn set_escrow_terms(
+    escrow: &mut Account<Escrow>,
+    campaign_id: [u8; 32],
+    authority: Pubkey,
+    refund_address: Pubkey,
+    payout_amount: u64,
+    max_claims: u32,
+    expires_at: i64,
+    winners_deadline: i64,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        payout_amount > 0
+            && max_claims > 0
+            && payout_amount.checked_mul(max_claims as u64).is_some()
+            && expires_at > now
+            && winners_deadline >= 0
+            && winners_deadline < expires_at,
+        ErrorCode::InvalidEscrowTerms
+    );
+
+    escrow.version = Escrow::VERSION;
+    escrow.campaign_id = campaign_id;
+    escrow.authority = authority;
+    escrow.refund_address = refund_address;
+    escrow.payout_amount = payout_amount;
+    escrow.max_claims = max_claims;
+    escrow.expires_at = expires_at;
+    escrow.winners_deadline = winners_deadline;
+    escrow.deposited = 0;
+    escrow.released = 0;
+    escrow.is_closed = false;
+    escrow.claim_root = [0u8; 32];
+    escrow.claim_verifier_program = CLAIM_VERIFIER_PROGRAM_ID;
+    escrow.claim_count = 0;
+    escrow.equal_distribution_settled = false;
+
+    emit!(EscrowInitialized {
+        schema_version: EVENT_SCHEMA_VERSION,
+        campaign_id,
+        escrow: escrow.key(),
+        authority,
+        refund_address,
+        payout_amount,
+        max_claims,
+        expires_at,
+        winners_deadline,
+        mint: escrow.mint,
+        decimals: escrow.decimals,
+    });
+
+    Ok(())
+}
+
+/// Deposits into the vault. Anyone may fund an open escrow.
+///
+/// SPL deposits are recorded by the vault's balance change, so mints with a
+/// transfer fee are credited net of it.
+pub(crate) fn fund_escrow<'info>(
+    ctx: Context<'_, '_, '_, 'info, FundEscrow<'info>>,
+    campaign_id: [u8; 32],
+    amount: u64,
+) -> Result<()> {
+    require!(!ctx.accounts.escrow.is_closed, ErrorCode::EscrowClosed);
+    require!(amount > 0, ErrorCode::InvalidEscrowTerms);
+
+    let accounts = &ctx.accounts;
+    let received = match accounts
+        .escrow
+        .tokens(&accounts.vault, &accounts.mint, &accounts.token_program)?
+    {
+        Some(tokens) => {
+            let source = accounts
+                .funder_token_account
+                .as_ref()
+                .ok_or(ErrorCode::InvalidEscrowVault)?;
+            let cpi_ctx = CpiContext::new(
+                tokens.token_program.to_account_info(),
+                TransferChecked {
+                    from: source.to_account_info(),
+                    mint: tokens.mint.to_account_info(),
+                    to: tokens.vault.to_account_info(),
+                    authority: accounts.funder.to_account_info(),
+                },
+            );
+            transfer_checked(cpi_ctx, amount, tokens.mint.decimals)?;
+            let before = tokens.vault.amount;
+            let mut vault = tokens.vault.clone();
+            vault.reload()?;
+            vault.amount.saturating_sub(before)
+        }
+        None => {
+            let cpi_ctx = CpiContext::new(
+                accounts.system_program.to_account_info(),
+                Transfer {
+                    from: accounts.funder.to_account_info(),
+                    to: accounts.escrow.to_account_info(),
+                },
+            );
+            transfer(cpi_ctx, amount)?;
+            amount
+        }
+    };
+
+    let escrow = &mut ctx.accounts.escrow;
+    escrow.deposited = escrow
+        .deposited
+        .checked_add(received)
+        .ok_or(ErrorCode::InvalidEscrowTerms)?;
+
+    emit!(EscrowFunded {
+        schema_version: EVENT_SCHEMA_VERSION,
+        campaign_id,
+        escrow: escrow.key(),
+        funder: ctx.accounts.funder.key(),
+        amount: received,
+        deposited: escrow.deposited,
+    });
+
+    Ok(())
+}
+
+/// Returns the whole balance to the refund address and closes the escrow.
+///
+/// Anyone may call it once the escrow has expired, or earlier when the final
+/// round of the campaign's dispute closed on `RefundHost` or `Tie` and can no
+/// longer be appealed. SPL escrows refund to a token account owned by the
+/// refund address.
+pub(crate) fn refund_escrow<'info>(
+    ctx: Context<'_, '_, '_, 'info, RefundEscrow<'info>>,
+    campaign_id: [u8; 32],
+) -> Result<()> {
+    require!(!ctx.accounts.escrow.is_closed, ErrorCode::EscrowClosed);
+
+    let expired = Clock::get()?.unix_timestamp > ctx.accounts.escrow.expires_at;
+    if !expired {
+        let voting_pool = ctx
+            .accounts
+            .voting_pool
+            .as_ref()
+            .ok_or(ErrorCode::EscrowReleaseLocked)?;
+        let outcome = dispute_outcome(&ctx.accounts.escrow, voting_pool, &campaign_id)?;
+        require!(
+            matches!(outcome, VoteOutcome::RefundHost | VoteOutcome::Tie),
+            ErrorCode::EscrowReleaseLocked
+        );
+    }
+
+    let accounts = &ctx.accounts;
+    let tokens = accounts
+        .escrow
+        .tokens(&accounts.vault, &accounts.mint, &accounts.token_program)?;
+    let destination = match &tokens {
+        Some(_) => accounts
+            .refund_token_account
+            .as_ref()
+            .ok_or(ErrorCode::InvalidEscrowVault)?
+            .to_account_info(),
+        None => accounts.refund_address.clone(),
+    };
+    let amount = escrow_balance(&accounts.escrow, tokens.as_ref())?;
+    release_funds(
+        &accounts.escrow,
+        ctx.bumps.escrow,
+        tokens.as_ref(),
+        &destination,
+        amount,
+    )?;
+
+    let escrow = &mut ctx.accounts.escrow;
+    escrow.released = escrow.released.saturating_add(amount);
+    escrow.is_closed = true;
+
+    emit!(EscrowRefunded {
+        schema_version: EVENT_SCHEMA_VERSION,
+        campaign_id,
+        escrow: escrow.key(),
+        refund_address: escrow.refund_address,
+        amount,
+        expired,
+    });
+
+    Ok(())
+}
+
+/// Outcome of a closed final dispute round belonging to `campaign_id`.
+///
+/// Appeal rounds live under derived ids, so the pool's round is used to derive
+/// the id it must carry. The pool must also be run by the escrow's authority,
+/// since anyone can open a pool for a campaign without a `Campaign` account,
+/// and its appeal window must have passed.
+pub(crate) fn dispute_outcome(
+    escrow: &Escrow,
+    voting_pool: &VotingPool,
+    campaign_id: &[u8; 32],
+) -> Result<VoteOutcome> {
+    require!(
+        voting_pool.campaign_id == round_campaign_id(campaign_id, voting_pool.round),
+        ErrorCode::EscrowReleaseLocked
+    );
+    require_keys_eq!(voting_pool.authority, escrow.authority, ErrorCode::Unauthorized);
+    require!(
+        !voting_pool.is_active
+            && voting_pool.is_final_round()
+            && voting_pool.outcome != VoteOutcome::Pending,
+        ErrorCode::EscrowReleaseLocked
+    );
+    require!(
+        !voting_pool.appeal_open(Clock::get()?.unix_timestamp),
+        ErrorCode::EscrowReleaseLocked
+    );
+    Ok(voting_pool.outcome)
+}
+
+/// Decodes the escrow stored at `info`, or `None` while none has been opened.
+///
+/// Callers constrain `info` to the campaign's `[b"escrow", campaign_id]`
+/// address, so an existing escrow can't be left out of the instruction.
+pub(crate) fn read_escrow(info: &AccountInfo) -> Result<Option<Escrow>> {
+    if info.data_is_empty() {
+        return Ok(None);
+    }
+    require_keys_eq!(*info.owner, crate::ID, ErrorCode::InvalidEscrowVault);
+    let data = info.try_borrow_data()?;
+    Ok(Some(Escrow::try_deserialize(&mut &data[..])?))
+}
+
+/// Funds the escrow can release: the vault's token balance, or lamports held
+/// above the escrow's rent-exempt minimum.
+pub(crate) fn escrow_balance(escrow: &Account<Escrow>, tokens: Option<&EscrowTokens>) -> Result<u64> {
+    if let Some(tokens) = tokens {
+        return Ok(tokens.vault.amount);
+    }
+    let info = escrow.to_account_info();
+    let rent = Rent::get()?.minimum_balance(info.data_len());
+    Ok(info.lamports().saturating_sub(rent))
+}
+
+/// Pays `amount` out of the vault to `to`, a wallet for SOL escrows or a token
+/// account of the mint for SPL escrows.
+pub(crate) fn release_funds<'info>(
+    escrow: &Account<'info, Escrow>,
+    bump: u8,
+    tokens: Option<&EscrowTokens<'_, 'info>>,
+    to: &AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    require!(
+        amount <= escrow_balance(escrow, tokens)?,
+        ErrorCode::InsufficientEscrowBalance
+    );
+    let Some(tokens) = tokens else {
+        let info = escrow.to_account_info();
+        **info.try_borrow_mut_lamports()? -= amount;
+        **to.try_borrow_mut_lamports()? += amount;
+        return Ok(());
+    };
+
+    let bump = [bump];
+    let seeds: &[&[u8]] = &[b"escrow", escrow.campaign_id.as_ref(), &bump];
+    let signer_seeds = [seeds];
+    let cpi_ctx = CpiContext::new_with_signer(
+        tokens.token_program.to_account_info(),
+        TransferChecked {
+            from: tokens.vault.to_account_info(),
+            mint: tokens.mint.to_account_info(),
+            to: to.clone(),
+            authority: escrow.to_account_info(),
+        },
+        &signer_seeds,
+    );
+    transfer_checked(cpi_ctx, amount, tokens.mint.decimals)
+}
@@ -0,0 +1,381 @@
+use anchor_lang::prelude::*;
+use crate::evidence::SubmitterClass;
+use crate::voting::{BallotType, OutcomePolicy, RevealMode, VoteOutcome};
+
+/// Bumped whenever an event layout changes so indexers can branch on it.
+pub const EVENT_SCHEMA_VERSION: u8 = 15;
+
+#[event]
+pub struct VotingPoolInitialized {
+    pub schema_version: u8,
+    pub campaign_id: [u8; 32],
+    pub voting_pool: Pubkey,
+    pub authority: Pubkey,
+    pub eligibility_root: [u8; 32],
+    pub zk_verifier_program: Pubkey,
+    pub encrypt_turnout: bool,
+    pub reveal_mode: RevealMode,
+    pub min_votes: u64,
+    pub public_reveal: bool,
+    pub weighted: bool,
+    pub allow_revote: bool,
+    pub ballot_type: BallotType,
+    pub option_count: u8,
+    pub question_count: u8,
+    pub eligible_count: u64,
+    pub appeal_bond: u64,
+    pub max_appeal_rounds: u8,
+    pub outcome_policy: OutcomePolicy,
+    pub outcome_hook_program: Pubkey,
+}
+
+#[event]
+pub struct EligibilityRootUpdated {
+    pub schema_version: u8,
+    pub campaign_id: [u8; 32],
+    pub voting_pool: Pubkey,
+    pub eligibility_root: [u8; 32],
+    pub eligible_count: u64,
+}
+
+#[event]
+pub struct VoteCast {
+    pub schema_version: u8,
+    pub campaign_id: [u8; 32],
+    pub voting_pool: Pubkey,
+    pub nullifier: [u8; 32],
+    pub weight: u64,
+    /// Whether delegated weight was added; the amount stays encrypted.
+    pub delegated: bool,
+    /// `None` when the pool keeps turnout encrypted.
+    pub total_votes: Option<u64>,
+}
+
+#[event]
+pub struct VoteRecast {
+    pub schema_version: u8,
+    pub campaign_id: [u8; 32],
+    pub voting_pool: Pubkey,
+    pub nullifier: [u8; 32],
+    pub sequence: u64,
+    pub weight: u64,
+}
+
+// The delegated weight stays encrypted on the `Delegation` account.
+#[event]
+pub struct VoteDelegated {
+    pub schema_version: u8,
+    pub campaign_id: [u8; 32],
+    pub voting_pool: Pubkey,
+    pub nullifier: [u8; 32],
+    pub delegate: [u8; 32],
+}
+
+#[event]
+pub struct VotingClosed {
+    pub schema_version: u8,
+    pub campaign_id: [u8; 32],
+    pub voting_pool: Pubkey,
+    /// Empty for outcome-only pools, which grant access after close.
+    pub decryptors: Vec<Pubkey>,
+    pub public_reveal: bool,
+    /// `None` when the pool keeps turnout encrypted.
+    pub total_votes: Option<u64>,
+}
+
+// The result stays encrypted until someone decrypts it and confirms.
+#[event]
+pub struct EarlyDecisionChecked {
+    pub schema_version: u8,
+    pub campaign_id: [u8; 32],
+    pub voting_pool: Pubkey,
+    pub requester: Pubkey,
+}
+
+#[event]
+pub struct EarlyDecisionConfirmed {
+    pub schema_version: u8,
+    pub campaign_id: [u8; 32],
+    pub voting_pool: Pubkey,
+}
+
+// Emitted instead of `VotingClosed` when turnout is below the anonymity threshold.
+#[event]
+pub struct VotingVoided {
+    pub schema_version: u8,
+    pub campaign_id: [u8; 32],
+    pub voting_pool: Pubkey,
+    pub total_votes: u64,
+    pub min_votes: u64,
+    pub outcome: VoteOutcome,
+}
+
+// `campaign_id` is the appealed pool's; the round is keyed by `round_campaign_id`.
+#[event]
+pub struct AppealOpened {
+    pub schema_version: u8,
+    pub campaign_id: [u8; 32],
+    pub parent_pool: Pubkey,
+    pub voting_pool: Pubkey,
+    pub round: u8,
+    pub round_campaign_id: [u8; 32],
+    pub appellant: Pubkey,
+    pub appeal_bond: u64,
+    pub eligibility_root: [u8; 32],
+    pub voting_ends_at: i64,
+}
+
+#[event]
+pub struct AppealBondSettled {
+    pub schema_version: u8,
+    pub campaign_id: [u8; 32],
+    pub voting_pool: Pubkey,
+    pub round: u8,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    /// Whether the round reversed its parent and the appellant was repaid.
+    pub refunded: bool,
+}
+
+#[event]
+pub struct CampaignInitialized {
+    pub schema_version: u8,
+    pub campaign_id: [u8; 32],
+    pub campaign: Pubkey,
+    pub authority: Pubkey,
+    pub eligibility_root: [u8; 32],
+    pub eligible_count: u64,
+    pub winners_deadline: i64,
+    pub dispute_window_seconds: i64,
+}
+
+#[event]
+pub struct CampaignEligibilityUpdated {
+    pub schema_version: u8,
+    pub campaign_id: [u8; 32],
+    pub campaign: Pubkey,
+    pub eligibility_root: [u8; 32],
+    pub eligible_count: u64,
+}
+
+// `escrow` is the default key when the campaign has no escrow.
+#[event]
+pub struct WinnersCommitted {
+    pub schema_version: u8,
+    pub campaign_id: [u8; 32],
+    pub campaign: Pubkey,
+    pub winners_root: [u8; 32],
+    pub winner_count: u32,
+    pub escrow: Pubkey,
+}
+
+#[event]
+pub struct DisputeOpened {
+    pub schema_version: u8,
+    pub campaign_id: [u8; 32],
+    pub campaign: Pubkey,
+    pub voting_pool: Pubkey,
+    pub opener: Pubkey,
+    pub voting_ends_at: i64,
+}
+
+#[event]
+pub struct EvidenceSubmitted {
+    pub schema_version: u8,
+    pub campaign_id: [u8; 32],
+    pub voting_pool: Pubkey,
+    pub index: u8,
+    pub content_hash: [u8; 32],
+    pub submitter: SubmitterClass,
+    pub submitted_at: i64,
+}
+
+#[event]
+pub struct EvidenceAccessGranted {
+    pub schema_version: u8,
+    pub campaign_id: [u8; 32],
+    pub voting_pool: Pubkey,
+    pub reader: Pubkey,
+    pub records: u8,
+}
+
+#[event]
+pub struct JurySeedCommitted {
+    pub schema_version: u8,
+    pub campaign_id: [u8; 32],
+    pub voting_pool: Pubkey,
+    pub seed_commitment: [u8; 32],
+    pub commit_slot: u64,
+    pub jury_size: u8,
+}
+
+#[event]
+pub struct JurySeedAbandoned {
+    pub schema_version: u8,
+    pub campaign_id: [u8; 32],
+    pub voting_pool: Pubkey,
+    pub commit_slot: u64,
+}
+
+// The seed and slot hash are published so anyone can re-derive the indices.
+#[event]
+pub struct JuryDrawn {
+    pub schema_version: u8,
+    pub campaign_id: [u8; 32],
+    pub voting_pool: Pubkey,
+    pub seed: [u8; 32],
+    pub slot: u64,
+    pub slot_hash: [u8; 32],
+    pub indices: Vec<u32>,
+    pub jury_root: [u8; 32],
+}
+
+#[event]
+pub struct OutcomeFinalized {
+    pub schema_version: u8,
+    pub campaign_id: [u8; 32],
+    pub voting_pool: Pubkey,
+    pub outcome: VoteOutcome,
+}
+
+#[event]
+pub struct OutcomeExecuted {
+    pub schema_version: u8,
+    pub campaign_id: [u8; 32],
+    pub voting_pool: Pubkey,
+    pub outcome: VoteOutcome,
+    pub hook_program: Pubkey,
+    pub requester: Pubkey,
+}
+
+#[event]
+pub struct EscrowInitialized {
+    pub schema_version: u8,
+    pub campaign_id: [u8; 32],
+    pub escrow: Pubkey,
+    pub authority: Pubkey,
+    pub refund_address: Pubkey,
+    pub payout_amount: u64,
+    pub max_claims: u32,
+    pub expires_at: i64,
+    pub winners_deadline: i64,
+    pub mint: Pubkey,
+    pub decimals: u8,
+}
+
+// `amount` is what the vault received, net of any transfer fee.
+#[event]
+pub struct EscrowFunded {
+    pub schema_version: u8,
+    pub campaign_id: [u8; 32],
+    pub escrow: Pubkey,
+    pub funder: Pubkey,
+    pub amount: u64,
+    pub deposited: u64,
+}
+
+// `expired` is false when the refund followed a dispute outcome.
+#[event]
+pub struct EscrowRefunded {
+    pub schema_version: u8,
+    pub campaign_id: [u8; 32],
+    pub escrow: Pubkey,
+    pub refund_address: Pubkey,
+    pub amount: u64,
+    pub expired: bool,
+}
+
+#[event]
+pub struct ClaimRootSet {
+    pub schema_version: u8,
+    pub campaign_id: [u8; 32],
+    pub escrow: Pubkey,
+    pub claim_root: [u8; 32],
+    pub claim_verifier_program: Pubkey,
+}
+
+#[event]
+pub struct ClaimPaid {
+    pub schema_version: u8,
+    pub campaign_id: [u8; 32],
+    pub escrow: Pubkey,
+    pub nullifier: [u8; 32],
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub claim_count: u32,
+}
+
+// `log_hash` lets auditors check a replayed log against the on-chain chain.
+#[event]
+pub struct ClaimRecorded {
+    pub schema_version: u8,
+    pub campaign_id: [u8; 32],
+    pub claim_log: Pubkey,
+    pub nullifier: [u8; 32],
+    pub index: u32,
+    pub log_hash: [u8; 32],
+    pub recorded_at: i64,
+}
+
+// `dust` is the remainder paid to the refund address; every leaf may claim `share`.
+#[event]
+pub struct EqualDistributionSettled {
+    pub schema_version: u8,
+    pub campaign_id: [u8; 32],
+    pub escrow: Pubkey,
+    pub voting_pool: Pubkey,
+    pub claim_root: [u8; 32],
+    pub eligible_count: u32,
+    pub share: u64,
+    pub dust: u64,
+}
+
+#[event]
+pub struct OutcomeAccessGranted {
+    pub schema_version: u8,
+    pub campaign_id: [u8; 32],
+    pub voting_pool: Pubkey,
+    pub decryptors: Vec<Pubkey>,
+}
+
+#[event]
+pub struct PublicAccessGranted {
+    pub schema_version: u8,
+    pub campaign_id: [u8; 32],
+    pub voting_pool: Pubkey,
+    pub requester: Pubkey,
+}
+
+#[event]
+pub struct AnalyticsInitialized {
+    pub schema_version: u8,
+    pub campaign_id: [u8; 32],
+    pub analytics: Pubkey,
+    pub authority: Pubkey,
+}
+
+// The increment stays encrypted; only the event type is public.
+#[event]
+pub struct AnalyticsEventTracked {
+    pub schema_version: u8,
+    pub campaign_id: [u8; 32],
+    pub analytics: Pubkey,
+    pub event_type: u8,
+}
+
+#[event]
+pub struct AnalyticsAccessGranted {
+    pub schema_version: u8,
+    pub campaign_id: [u8; 32],
+    pub analytics: Pubkey,
+    pub allowed_address: Pubkey,
+}
+
+#[event]
+pub struct AccountMigrated {
+    pub schema_version: u8,
+    pub campaign_id: [u8; 32],
+    pub account: Pubkey,
+    pub from_version: u8,
+    pub to_version: u8,
+}
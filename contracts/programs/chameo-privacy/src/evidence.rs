@@ -0,0 +1,262 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+use inco_lightning::cpi::accounts::Operation;
+use inco_lightning::cpi::new_euint128;
+use inco_lightning::ID as INCO_LIGHTNING_ID;
+use crate::ErrorCode;
+use crate::events::{EvidenceAccessGranted, EvidenceSubmitted, EVENT_SCHEMA_VERSION};
+use crate::handle::IncoHandle;
+use crate::voting::{
+    allow_handles, evidence_access_commitment, evidence_commitment, verify_ballot, VotingPool,
+};
+
+/// Records each submitter class may add, so neither side can crowd out the other.
+pub const MAX_EVIDENCE_PER_CLASS: usize = 16;
+
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SubmitterClass {
+    /// The pool authority, submitting for the host.
+    Host,
+    /// An eligible recipient, proven against the pool's eligibility root.
+    Recipient,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone)]
+pub struct EvidenceRecord {
+    /// Hash of the evidence content, which is stored off-chain encrypted under the key.
+    pub content_hash: [u8; 32],
+    /// Encrypted symmetric key; voters gain access with `grant_evidence_access`.
+    pub encrypted_key: IncoHandle,
+    pub submitter: SubmitterClass,
+    pub submitted_at: i64,
+}
+
+/// Append-only evidence for one pool, in submission order.
+#[account]
+#[derive(InitSpace)]
+pub struct EvidenceLog {
+    pub campaign_id: [u8; 32],
+    pub voting_pool: Pubkey,
+    #[max_len(2 * MAX_EVIDENCE_PER_CLASS)]
+    pub records: Vec<EvidenceRecord>,
+}
+
+/// Marks a recipient's evidence-domain nullifier once they have submitted, so
+/// each recipient fills at most one of the recipient slots.
+#[account]
+#[derive(InitSpace)]
+pub struct EvidenceNullifier {
+    pub campaign_id: [u8; 32],
+    pub value: [u8; 32],
+}
+
+#[derive(Accounts)]
+#[instruction(
+    campaign_id: [u8; 32],
+    content_hash: [u8; 32],
+    encrypted_key: Vec<u8>,
+    submitter: SubmitterClass,
+    nullifier_value: [u8; 32]
+)]
+pub struct SubmitEvidence<'info> {
+    #[account(
+        init_if_needed,
+        payer = submitter,
+        space = 8 + EvidenceLog::INIT_SPACE,
+        seeds = [b"evidence", campaign_id.as_ref()],
+        bump
+    )]
+    pub evidence_log: Account<'info, EvidenceLog>,
+    #[account(
+        seeds = [b"voting_pool", campaign_id.as_ref()],
+        bump
+    )]
+    pub voting_pool: Account<'info, VotingPool>,
+    /// Spends the recipient's nullifier; required for recipient submissions.
+    #[account(
+        init,
+        payer = submitter,
+        space = 8 + EvidenceNullifier::INIT_SPACE,
+        seeds = [b"evidence_nullifier", campaign_id.as_ref(), nullifier_value.as_ref()],
+        bump
+    )]
+    pub evidence_nullifier: Option<Account<'info, EvidenceNullifier>>,
+    #[account(mut)]
+    pub submitter: Signer<'info>,
+    /// CHECK: ZK verifier program, required for recipient submissions
+    pub zk_verifier_program: Option<AccountInfo<'info>>,
+    /// CHECK: Inco Lightning program
+    #[account(address = INCO_LIGHTNING_ID)]
+    pub inco_lightning_program: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(campaign_id: [u8; 32])]
+pub struct GrantEvidenceAccess<'info> {
+    #[account(
+        seeds = [b"evidence", campaign_id.as_ref()],
+        bump
+    )]
+    pub evidence_log: Account<'info, EvidenceLog>,
+    #[account(
+        seeds = [b"voting_pool", campaign_id.as_ref()],
+        bump
+    )]
+    pub voting_pool: Account<'info, VotingPool>,
+    #[account(mut)]
+    pub reader: Signer<'info>,
+    /// CHECK: ZK verifier program
+    pub zk_verifier_program: AccountInfo<'info>,
+    /// CHECK: Inco Lightning program
+    #[account(address = INCO_LIGHTNING_ID)]
+    pub inco_lightning_program: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Appends an evidence record while the pool is open.
+///
+/// Host records are signed by the pool authority. Recipient records carry a
+/// vote-circuit proof whose commitment covers the content hash and key
+/// ciphertext. Its nullifier is in the circuit's evidence domain rather than
+/// the vote nullifier, so spending it neither links the record to a ballot
+/// nor stops the recipient from voting.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn submit_evidence<'info>(
+    ctx: Context<'_, '_, '_, 'info, SubmitEvidence<'info>>,
+    campaign_id: [u8; 32],
+    content_hash: [u8; 32],
+    encrypted_key: Vec<u8>,
+    submitter: SubmitterClass,
+    nullifier_value: [u8; 32],
+    proof: Vec<u8>,
+    public_witness: Vec<u8>,
+) -> Result<()> {
+    let voting_pool = &ctx.accounts.voting_pool;
+    require!(voting_pool.is_active, ErrorCode::VotingNotActive);
+
+    match submitter {
+        SubmitterClass::Host => {
+            require!(
+                ctx.accounts.submitter.key() == voting_pool.authority,
+                ErrorCode::Unauthorized
+            );
+        }
+        SubmitterClass::Recipient => {
+            let zk_verifier_program = ctx
+                .accounts
+                .zk_verifier_program
+                .as_ref()
+                .ok_or(ErrorCode::InvalidZkVerifier)?;
+            let record_digest = hashv(&[content_hash.as_ref(), &encrypted_key]).to_bytes();
+            verify_ballot(
+                voting_pool,
+                zk_verifier_program,
+                &nullifier_value,
+                &proof,
+                &public_witness,
+                &evidence_commitment(&record_digest)?,
+            )?;
+            let evidence_nullifier = ctx
+                .accounts
+                .evidence_nullifier
+                .as_mut()
+                .ok_or(ErrorCode::NullifierMismatch)?;
+            evidence_nullifier.campaign_id = campaign_id;
+            evidence_nullifier.value = nullifier_value;
+        }
+    }
+
+    let evidence_log = &mut ctx.accounts.evidence_log;
+    let submitted = evidence_log
+        .records
+        .iter()
+        .filter(|record| record.submitter == submitter)
+        .count();
+    require!(
+        submitted < MAX_EVIDENCE_PER_CLASS,
+        ErrorCode::EvidenceLogFull
+    );
+
+    let cpi_ctx = CpiContext::new(
+        ctx.accounts.inco_lightning_program.to_account_info(),
+        Operation {
+            signer: ctx.accounts.submitter.to_account_info(),
+        },
+    );
+    let key = new_euint128(cpi_ctx, encrypted_key, 0)?;
+
+    let submitted_at = Clock::get()?.unix_timestamp;
+    if evidence_log.records.is_empty() {
+        evidence_log.campaign_id = campaign_id;
+        evidence_log.voting_pool = voting_pool.key();
+    }
+    evidence_log.records.push(EvidenceRecord {
+        content_hash,
+        encrypted_key: key.into(),
+        submitter,
+        submitted_at,
+    });
+
+    emit!(EvidenceSubmitted {
+        schema_version: EVENT_SCHEMA_VERSION,
+        campaign_id,
+        voting_pool: voting_pool.key(),
+        index: (evidence_log.records.len() - 1) as u8,
+        content_hash,
+        submitter,
+        submitted_at,
+    });
+
+    Ok(())
+}
+
+/// Lets an eligible voter decrypt every evidence key as `reader`.
+///
+/// The proof commits to the reader's address so it can't be replayed for
+/// another wallet, and its nullifier is in the evidence-access domain so the
+/// wallet can't be linked to a ballot or an evidence record.
+/// `remaining_accounts` holds one allowance per record, in order.
+pub(crate) fn grant_evidence_access<'info>(
+    ctx: Context<'_, '_, '_, 'info, GrantEvidenceAccess<'info>>,
+    campaign_id: [u8; 32],
+    nullifier_value: [u8; 32],
+    proof: Vec<u8>,
+    public_witness: Vec<u8>,
+) -> Result<()> {
+    let reader = ctx.accounts.reader.to_account_info();
+    verify_ballot(
+        &ctx.accounts.voting_pool,
+        &ctx.accounts.zk_verifier_program,
+        &nullifier_value,
+        &proof,
+        &public_witness,
+        &evidence_access_commitment(&reader.key())?,
+    )?;
+
+    let handles: Vec<u128> = ctx
+        .accounts
+        .evidence_log
+        .records
+        .iter()
+        .map(|record| record.encrypted_key.0)
+        .collect();
+    allow_handles(
+        &ctx.accounts.inco_lightning_program.to_account_info(),
+        &reader,
+        &ctx.accounts.system_program.to_account_info(),
+        &reader,
+        ctx.remaining_accounts,
+        &handles,
+    )?;
+
+    emit!(EvidenceAccessGranted {
+        schema_version: EVENT_SCHEMA_VERSION,
+        campaign_id,
+        voting_pool: ctx.accounts.voting_pool.key(),
+        reader: reader.key(),
+        records: handles.len() as u8,
+    });
+
+    Ok(())
+}
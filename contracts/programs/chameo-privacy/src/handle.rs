@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+use inco_lightning::types::{Ebool, Euint128};
+
+/// Inco handle as stored in program accounts.
+///
+/// `Euint128` and `Ebool` come from another crate and can't implement `Space`,
+/// so accounts hold this instead and convert at the CPI boundary. It serializes
+/// exactly like `Euint128`, so clients decode it the same way.
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct IncoHandle(pub u128);
+
+impl From<Euint128> for IncoHandle {
+    fn from(value: Euint128) -> Self {
+        Self(value.0)
+    }
+}
+
+impl From<IncoHandle> for Euint128 {
+    fn from(value: IncoHandle) -> Self {
+        Euint128(value.0)
+    }
+}
+
+impl From<Ebool> for IncoHandle {
+    fn from(value: Ebool) -> Self {
+        Self(value.0)
+    }
+}
+
+impl From<IncoHandle> for Ebool {
+    fn from(value: IncoHandle) -> Self {
+        Ebool(value.0)
+    }
+}
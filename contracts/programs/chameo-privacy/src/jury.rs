@@ -0,0 +1,370 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+use anchor_lang::solana_program::sysvar::slot_hashes::ID as SLOT_HASHES_SYSVAR_ID;
+use solana_poseidon::{hashv as poseidon_hashv, Endianness, Parameters};
+use crate::ErrorCode;
+use crate::events::{JuryDrawn, JurySeedAbandoned, JurySeedCommitted, EVENT_SCHEMA_VERSION};
+use crate::voting::VotingPool;
+
+/// Depth of the jury tree; `jury_vote_eligibility` must use the same.
+pub const JURY_DEPTH: usize = 6;
+pub const MAX_JURY_SIZE: usize = 1 << JURY_DEPTH;
+// SlotHashes is a u64 entry count followed by (slot, hash) pairs, newest first.
+const SLOT_HASHES_LEN_PREFIX: usize = 8;
+const SLOT_HASH_ENTRY_LEN: usize = 8 + 32;
+
+/// A pool's jury draw: the committed seed until it is revealed, then the jurors.
+#[account]
+#[derive(InitSpace)]
+pub struct Jury {
+    pub campaign_id: [u8; 32],
+    pub seed_commitment: [u8; 32],
+    pub commit_slot: u64,
+    pub jury_size: u8,
+    pub jury_verifier_program: Pubkey,
+    /// Eligibility set at commit; the pool's root is frozen until the draw.
+    pub eligibility_root: [u8; 32],
+    pub eligible_count: u64,
+    /// Drawn leaf indices into the eligibility tree, ascending; empty until drawn.
+    #[max_len(MAX_JURY_SIZE)]
+    pub indices: Vec<u32>,
+}
+
+#[derive(Accounts)]
+#[instruction(campaign_id: [u8; 32])]
+pub struct CommitJurySeed<'info> {
+    #[account(
+        mut,
+        seeds = [b"voting_pool", campaign_id.as_ref()],
+        bump,
+        constraint = voting_pool.authority == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub voting_pool: Account<'info, VotingPool>,
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Jury::INIT_SPACE,
+        seeds = [b"jury", campaign_id.as_ref()],
+        bump
+    )]
+    pub jury: Account<'info, Jury>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(campaign_id: [u8; 32])]
+pub struct DrawJury<'info> {
+    #[account(
+        mut,
+        seeds = [b"voting_pool", campaign_id.as_ref()],
+        bump
+    )]
+    pub voting_pool: Account<'info, VotingPool>,
+    #[account(
+        mut,
+        seeds = [b"jury", campaign_id.as_ref()],
+        bump
+    )]
+    pub jury: Account<'info, Jury>,
+    pub requester: Signer<'info>,
+    /// CHECK: SlotHashes sysvar, parsed by hand since it is too large to deserialize
+    #[account(address = SLOT_HASHES_SYSVAR_ID)]
+    pub slot_hashes: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(campaign_id: [u8; 32])]
+pub struct AbandonJurySeed<'info> {
+    #[account(
+        mut,
+        seeds = [b"voting_pool", campaign_id.as_ref()],
+        bump
+    )]
+    pub voting_pool: Account<'info, VotingPool>,
+    #[account(
+        seeds = [b"jury", campaign_id.as_ref()],
+        bump
+    )]
+    pub jury: Account<'info, Jury>,
+    pub requester: Signer<'info>,
+    /// CHECK: SlotHashes sysvar, parsed by hand since it is too large to deserialize
+    #[account(address = SLOT_HASHES_SYSVAR_ID)]
+    pub slot_hashes: AccountInfo<'info>,
+}
+
+/// Commits to the seed for a jury draw. The jury is drawn from the hash of the
+/// first slot after this one, which the authority can't know yet, and each pool
+/// gets a single commitment so a bad draw can't be retried.
+///
+/// The eligibility set is snapshotted here and `set_eligibility_root` is
+/// refused from now on, so leaves can't be reordered once the slot is known.
+/// Ballots are refused until the draw, since a vote would rule it out.
+pub(crate) fn commit_jury_seed<'info>(
+    ctx: Context<'_, '_, '_, 'info, CommitJurySeed<'info>>,
+    campaign_id: [u8; 32],
+    seed_commitment: [u8; 32],
+    jury_size: u8,
+    jury_verifier_program: Pubkey,
+) -> Result<()> {
+    let voting_pool = &mut ctx.accounts.voting_pool;
+    require!(voting_pool.is_active, ErrorCode::VotingNotActive);
+    require!(!voting_pool.has_votes, ErrorCode::JuryUnavailable);
+    // Jurors vote once each, through a single-question circuit.
+    require!(
+        !voting_pool.weighted && voting_pool.question_count <= 1,
+        ErrorCode::JuryUnavailable
+    );
+    require!(
+        jury_size > 0
+            && jury_size as usize <= MAX_JURY_SIZE
+            && jury_size as u64 <= voting_pool.eligible_count
+            && voting_pool.eligible_count <= u32::MAX as u64,
+        ErrorCode::InvalidPoolOptions
+    );
+
+    let commit_slot = Clock::get()?.slot;
+    voting_pool.jury_pending = true;
+    let jury = &mut ctx.accounts.jury;
+    jury.campaign_id = campaign_id;
+    jury.seed_commitment = seed_commitment;
+    jury.commit_slot = commit_slot;
+    jury.jury_size = jury_size;
+    jury.jury_verifier_program = jury_verifier_program;
+    jury.eligibility_root = voting_pool.eligibility_root;
+    jury.eligible_count = voting_pool.eligible_count;
+
+    emit!(JurySeedCommitted {
+        schema_version: EVENT_SCHEMA_VERSION,
+        campaign_id,
+        voting_pool: voting_pool.key(),
+        seed_commitment,
+        commit_slot,
+        jury_size,
+    });
+
+    Ok(())
+}
+
+/// Reveals the seed and draws the jury from it and the committed slot hash.
+///
+/// Stores the root over the drawn leaf indices on the pool and switches ballots
+/// to the jury verifier, whose proofs also show the voter's index is in that root.
+pub(crate) fn draw_jury<'info>(
+    ctx: Context<'_, '_, '_, 'info, DrawJury<'info>>,
+    campaign_id: [u8; 32],
+    seed: [u8; 32],
+) -> Result<()> {
+    let voting_pool = &mut ctx.accounts.voting_pool;
+    require!(voting_pool.is_active, ErrorCode::VotingNotActive);
+    require!(!voting_pool.has_votes, ErrorCode::JuryUnavailable);
+    require!(!voting_pool.has_jury(), ErrorCode::JuryAlreadyDrawn);
+    require!(voting_pool.jury_pending, ErrorCode::JuryUnavailable);
+
+    let jury = &mut ctx.accounts.jury;
+    require!(
+        hashv(&[seed.as_ref()]).to_bytes() == jury.seed_commitment,
+        ErrorCode::InvalidJurySeed
+    );
+
+    let (slot, slot_hash) = slot_hash_after(&ctx.accounts.slot_hashes, jury.commit_slot)?;
+    let randomness = hashv(&[b"jury", seed.as_ref(), slot_hash.as_ref()]).to_bytes();
+    let indices = draw_indices(&randomness, jury.jury_size, jury.eligible_count);
+    let jury_root = jury_root(&indices)?;
+
+    jury.indices = indices.clone();
+    voting_pool.jury_root = jury_root;
+    voting_pool.jury_verifier_program = jury.jury_verifier_program;
+    voting_pool.jury_pending = false;
+
+    emit!(JuryDrawn {
+        schema_version: EVENT_SCHEMA_VERSION,
+        campaign_id,
+        voting_pool: voting_pool.key(),
+        seed,
+        slot,
+        slot_hash,
+        indices,
+        jury_root,
+    });
+
+    Ok(())
+}
+
+/// Lets a pool whose jury can no longer be drawn take ballots again, without
+/// a jury. Anyone may call it once SlotHashes no longer reaches back to the
+/// commit slot, e.g. because the seed was never revealed.
+///
+/// The jury account stays, so the pool can't commit to a second seed.
+pub(crate) fn abandon_jury_seed<'info>(
+    ctx: Context<'_, '_, '_, 'info, AbandonJurySeed<'info>>,
+    campaign_id: [u8; 32],
+) -> Result<()> {
+    let voting_pool = &mut ctx.accounts.voting_pool;
+    require!(voting_pool.jury_pending, ErrorCode::JuryUnavailable);
+    let commit_slot = ctx.accounts.jury.commit_slot;
+    require!(
+        oldest_slot(&ctx.accounts.slot_hashes)? > commit_slot,
+        ErrorCode::JuryDrawPending
+    );
+    voting_pool.jury_pending = false;
+
+    emit!(JurySeedAbandoned {
+        schema_version: EVENT_SCHEMA_VERSION,
+        campaign_id,
+        voting_pool: voting_pool.key(),
+        commit_slot,
+    });
+
+    Ok(())
+}
+
+/// First recorded slot after `commit_slot` and its hash.
+///
+/// Requires the sysvar to still reach back to `commit_slot`, so the draw must
+/// happen within its ~512-slot history; otherwise later slots could be skipped.
+fn slot_hash_after(slot_hashes: &AccountInfo, commit_slot: u64) -> Result<(u64, [u8; 32])> {
+    let data = slot_hashes.try_borrow_data()?;
+    require!(
+        data.len() >= SLOT_HASHES_LEN_PREFIX,
+        ErrorCode::JurySlotHashUnavailable
+    );
+    let entries = u64::from_le_bytes(data[..SLOT_HASHES_LEN_PREFIX].try_into().unwrap()) as usize;
+
+    let mut after = None;
+    for i in 0..entries {
+        let start = SLOT_HASHES_LEN_PREFIX + i * SLOT_HASH_ENTRY_LEN;
+        let Some(entry) = data.get(start..start + SLOT_HASH_ENTRY_LEN) else {
+            break;
+        };
+        let slot = u64::from_le_bytes(entry[..8].try_into().unwrap());
+        if slot <= commit_slot {
+            return after.ok_or_else(|| ErrorCode::JurySlotHashUnavailable.into());
+        }
+        after = Some((slot, entry[8..].try_into().unwrap()));
+    }
+    err!(ErrorCode::JurySlotHashUnavailable)
+}
+
+/// Oldest slot SlotHashes still records.
+fn oldest_slot(slot_hashes: &AccountInfo) -> Result<u64> {
+    let data = slot_hashes.try_borrow_data()?;
+    oldest_slot_in(&data)
+}
+
+fn oldest_slot_in(data: &[u8]) -> Result<u64> {
+    require!(
+        data.len() >= SLOT_HASHES_LEN_PREFIX,
+        ErrorCode::JurySlotHashUnavailable
+    );
+    let entries = u64::from_le_bytes(data[..SLOT_HASHES_LEN_PREFIX].try_into().unwrap()) as usize;
+    let stored = (data.len() - SLOT_HASHES_LEN_PREFIX) / SLOT_HASH_ENTRY_LEN;
+    let last = entries
+        .min(stored)
+        .checked_sub(1)
+        .ok_or(ErrorCode::JurySlotHashUnavailable)?;
+    let start = SLOT_HASHES_LEN_PREFIX + last * SLOT_HASH_ENTRY_LEN;
+    Ok(u64::from_le_bytes(data[start..start + 8].try_into().unwrap()))
+}
+
+/// Distinct leaf indices below `eligible_count`, ascending.
+fn draw_indices(randomness: &[u8; 32], jury_size: u8, eligible_count: u64) -> Vec<u32> {
+    let mut indices: Vec<u32> = Vec::with_capacity(jury_size as usize);
+    let mut counter: u32 = 0;
+    while indices.len() < jury_size as usize {
+        let digest = hashv(&[randomness.as_ref(), &counter.to_le_bytes()]).to_bytes();
+        let index = (u64::from_le_bytes(digest[..8].try_into().unwrap()) % eligible_count) as u32;
+        if !indices.contains(&index) {
+            indices.push(index);
+        }
+        counter += 1;
+    }
+    indices.sort_unstable();
+    indices
+}
+
+/// Poseidon root over `poseidon(index + 1)` leaves, zero-padded to a full tree.
+///
+/// Offsetting the index keeps leaf 0 distinct from padding.
+fn jury_root(indices: &[u32]) -> Result<[u8; 32]> {
+    let mut layer: Vec<[u8; 32]> = Vec::with_capacity(MAX_JURY_SIZE);
+    for index in indices {
+        let mut field = [0u8; 32];
+        field[24..].copy_from_slice(&(*index as u64 + 1).to_be_bytes());
+        layer.push(poseidon(&[field.as_ref()])?);
+    }
+    layer.resize(MAX_JURY_SIZE, [0u8; 32]);
+
+    while layer.len() > 1 {
+        layer = layer
+            .chunks(2)
+            .map(|pair| poseidon(&[pair[0].as_ref(), pair[1].as_ref()]))
+            .collect::<Result<_>>()?;
+    }
+    Ok(layer[0])
+}
+
+fn poseidon(fields: &[&[u8]]) -> Result<[u8; 32]> {
+    let hash = poseidon_hashv(Parameters::Bn254X5, Endianness::BigEndian, fields)
+        .map_err(|_| ErrorCode::InvalidPoseidonInput)?;
+    Ok(hash.to_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn draw_indices_are_distinct_sorted_and_in_range() {
+        let randomness = [1u8; 32];
+        let indices = draw_indices(&randomness, 16, 40);
+        assert_eq!(indices.len(), 16);
+        assert!(indices.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(indices.iter().all(|index| (*index as u64) < 40));
+    }
+
+    #[test]
+    fn draw_indices_is_deterministic_in_the_randomness() {
+        assert_eq!(draw_indices(&[1u8; 32], 8, 100), draw_indices(&[1u8; 32], 8, 100));
+        assert_ne!(draw_indices(&[1u8; 32], 8, 100), draw_indices(&[2u8; 32], 8, 100));
+    }
+
+    #[test]
+    fn draw_indices_takes_every_leaf_when_the_jury_is_everyone() {
+        assert_eq!(draw_indices(&[5u8; 32], 6, 6), vec![0, 1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn jury_root_keeps_leaf_zero_apart_from_padding() {
+        assert_ne!(jury_root(&[0]).unwrap(), jury_root(&[]).unwrap());
+    }
+
+    #[test]
+    fn jury_root_depends_on_the_drawn_indices() {
+        assert_eq!(jury_root(&[1, 4]).unwrap(), jury_root(&[1, 4]).unwrap());
+        assert_ne!(jury_root(&[1, 4]).unwrap(), jury_root(&[1, 5]).unwrap());
+        assert_ne!(jury_root(&[1, 4]).unwrap(), jury_root(&[4, 1]).unwrap());
+    }
+
+    fn slot_hashes_data(slots: &[u64]) -> Vec<u8> {
+        let mut data = (slots.len() as u64).to_le_bytes().to_vec();
+        for slot in slots {
+            data.extend_from_slice(&slot.to_le_bytes());
+            data.extend_from_slice(&[0u8; 32]);
+        }
+        data
+    }
+
+    #[test]
+    fn oldest_slot_is_the_last_entry() {
+        assert_eq!(oldest_slot_in(&slot_hashes_data(&[90, 85, 80])).unwrap(), 80);
+        assert!(oldest_slot_in(&slot_hashes_data(&[])).is_err());
+    }
+
+    #[test]
+    fn jury_root_fits_a_full_jury() {
+        let indices: Vec<u32> = (0..MAX_JURY_SIZE as u32).collect();
+        assert!(jury_root(&indices).is_ok());
+    }
+}
@@ -4,78 +4,430 @@
 use anchor_lang::prelude::*;
 
 pub mod voting;
+pub mod delegation;
+pub mod early_decision;
+pub mod appeal;
+pub mod jury;
+pub mod campaign;
+pub mod evidence;
+pub mod outcome_hook;
+pub mod escrow;
+pub mod claim;
+pub mod settlement;
 pub mod analytics;
+pub mod events;
+pub mod handle;
+mod attestation;
+mod migration;
 
 pub use voting::*;
+pub use delegation::*;
+pub use early_decision::*;
+pub use appeal::*;
+pub use jury::*;
+pub use campaign::*;
+pub use evidence::*;
+pub use outcome_hook::*;
+pub use escrow::*;
+pub use claim::*;
+pub use settlement::*;
 pub use analytics::*;
+pub use events::*;
+pub use handle::*;
 
 declare_id!("FsoGyYnvQDu5zXHmWHiyCxi7nWMr7RYxB1zGgz8ciJVM");
 
-#[program]
-pub mod chameo_privacy {
+// `#[program]` emits its IDL instructions beside the module, and they still
+// call the deprecated `AccountInfo::realloc`.
+#[allow(deprecated)]
+mod interface {
     use super::*;
 
-    pub fn initialize_voting_pool<'info>(
-        ctx: Context<'_, '_, '_, 'info, InitializeVotingPool<'info>>,
-        campaign_id: [u8; 32],
-        eligibility_root: [u8; 32],
-        zk_verifier_program: Pubkey,
-    ) -> Result<()> {
-        voting::initialize_voting_pool(ctx, campaign_id, eligibility_root, zk_verifier_program)
-    }
+    #[program]
+    pub mod chameo_privacy {
+        use super::*;
 
-    pub fn cast_vote_zk<'info>(
-        ctx: Context<'_, '_, '_, 'info, CastVoteZk<'info>>,
-        campaign_id: [u8; 32],
-        nullifier_value: [u8; 32],
-        proof: Vec<u8>,
-        public_witness: Vec<u8>,
-        encrypted_vote: Vec<u8>,
-    ) -> Result<()> {
-        voting::cast_vote_zk(ctx, campaign_id, nullifier_value, proof, public_witness, encrypted_vote)
-    }
+        pub fn initialize_voting_pool<'info>(
+            ctx: Context<'_, '_, '_, 'info, InitializeVotingPool<'info>>,
+            campaign_id: [u8; 32],
+            eligibility_root: [u8; 32],
+            zk_verifier_program: Pubkey,
+            options: VotingPoolOptions,
+        ) -> Result<()> {
+            voting::initialize_voting_pool(ctx, campaign_id, eligibility_root, zk_verifier_program, options)
+        }
 
-    pub fn close_voting<'info>(
-        ctx: Context<'_, '_, '_, 'info, CloseVoting<'info>>,
-        campaign_id: [u8; 32],
-        allowed_address: Pubkey,
-    ) -> Result<()> {
-        voting::close_voting(ctx, campaign_id, allowed_address)
-    }
+        pub fn cast_vote_zk<'info>(
+            ctx: Context<'_, '_, '_, 'info, CastVoteZk<'info>>,
+            campaign_id: [u8; 32],
+            nullifier_value: [u8; 32],
+            proof: Vec<u8>,
+            public_witness: Vec<u8>,
+            encrypted_vote: Vec<u8>,
+        ) -> Result<()> {
+            voting::cast_vote_zk(ctx, campaign_id, nullifier_value, proof, public_witness, encrypted_vote)
+        }
 
-    pub fn set_eligibility_root<'info>(
-        ctx: Context<'_, '_, '_, 'info, SetEligibilityRoot<'info>>,
-        campaign_id: [u8; 32],
-        eligibility_root: [u8; 32],
-    ) -> Result<()> {
-        voting::set_eligibility_root(ctx, campaign_id, eligibility_root)
-    }
+        pub fn recast_vote_zk<'info>(
+            ctx: Context<'_, '_, '_, 'info, RecastVoteZk<'info>>,
+            campaign_id: [u8; 32],
+            nullifier_value: [u8; 32],
+            sequence: u64,
+            proof: Vec<u8>,
+            public_witness: Vec<u8>,
+            encrypted_vote: Vec<u8>,
+        ) -> Result<()> {
+            voting::recast_vote_zk(
+                ctx,
+                campaign_id,
+                nullifier_value,
+                sequence,
+                proof,
+                public_witness,
+                encrypted_vote,
+            )
+        }
 
-    pub fn initialize_analytics<'info>(
-        ctx: Context<'_, '_, '_, 'info, InitializeAnalytics<'info>>,
-        campaign_id: [u8; 32],
-    ) -> Result<()> {
-        analytics::initialize_analytics(ctx, campaign_id)
-    }
+        pub fn delegate_vote<'info>(
+            ctx: Context<'_, '_, '_, 'info, DelegateVote<'info>>,
+            campaign_id: [u8; 32],
+            nullifier_value: [u8; 32],
+            delegate_nullifier: [u8; 32],
+            proof: Vec<u8>,
+            public_witness: Vec<u8>,
+        ) -> Result<()> {
+            delegation::delegate_vote(
+                ctx,
+                campaign_id,
+                nullifier_value,
+                delegate_nullifier,
+                proof,
+                public_witness,
+            )
+        }
 
-    pub fn track_event<'info>(
-        ctx: Context<'_, '_, '_, 'info, TrackEvent<'info>>,
-        campaign_id: [u8; 32],
-        encrypted_increment: Vec<u8>,
-        event_type: u8,
-    ) -> Result<()> {
-        analytics::track_event(ctx, campaign_id, encrypted_increment, event_type)
-    }
+        pub fn check_early_decision<'info>(
+            ctx: Context<'_, '_, '_, 'info, CheckEarlyDecision<'info>>,
+            campaign_id: [u8; 32],
+        ) -> Result<()> {
+            early_decision::check_early_decision(ctx, campaign_id)
+        }
+
+        pub fn grant_early_decision_access<'info>(
+            ctx: Context<'_, '_, '_, 'info, GrantEarlyDecisionAccess<'info>>,
+            campaign_id: [u8; 32],
+        ) -> Result<()> {
+            early_decision::grant_early_decision_access(ctx, campaign_id)
+        }
+
+        pub fn confirm_early_decision<'info>(
+            ctx: Context<'_, '_, '_, 'info, ConfirmEarlyDecision<'info>>,
+            campaign_id: [u8; 32],
+        ) -> Result<()> {
+            early_decision::confirm_early_decision(ctx, campaign_id)
+        }
+
+        pub fn close_voting<'info>(
+            ctx: Context<'_, '_, '_, 'info, CloseVoting<'info>>,
+            campaign_id: [u8; 32],
+            allowed_address: Pubkey,
+        ) -> Result<()> {
+            voting::close_voting(ctx, campaign_id, allowed_address)
+        }
+
+        pub fn appeal_voting<'info>(
+            ctx: Context<'_, '_, '_, 'info, AppealVoting<'info>>,
+            campaign_id: [u8; 32],
+            round_campaign_id: [u8; 32],
+            window_seconds: i64,
+        ) -> Result<()> {
+            appeal::appeal_voting(ctx, campaign_id, round_campaign_id, window_seconds)
+        }
+
+        pub fn settle_appeal_bond<'info>(
+            ctx: Context<'_, '_, '_, 'info, SettleAppealBond<'info>>,
+            campaign_id: [u8; 32],
+            round_campaign_id: [u8; 32],
+        ) -> Result<()> {
+            appeal::settle_appeal_bond(ctx, campaign_id, round_campaign_id)
+        }
+
+        pub fn commit_jury_seed<'info>(
+            ctx: Context<'_, '_, '_, 'info, CommitJurySeed<'info>>,
+            campaign_id: [u8; 32],
+            seed_commitment: [u8; 32],
+            jury_size: u8,
+            jury_verifier_program: Pubkey,
+        ) -> Result<()> {
+            jury::commit_jury_seed(ctx, campaign_id, seed_commitment, jury_size, jury_verifier_program)
+        }
+
+        pub fn draw_jury<'info>(
+            ctx: Context<'_, '_, '_, 'info, DrawJury<'info>>,
+            campaign_id: [u8; 32],
+            seed: [u8; 32],
+        ) -> Result<()> {
+            jury::draw_jury(ctx, campaign_id, seed)
+        }
+
+        pub fn abandon_jury_seed<'info>(
+            ctx: Context<'_, '_, '_, 'info, AbandonJurySeed<'info>>,
+            campaign_id: [u8; 32],
+        ) -> Result<()> {
+            jury::abandon_jury_seed(ctx, campaign_id)
+        }
+
+        pub fn initialize_campaign<'info>(
+            ctx: Context<'_, '_, '_, 'info, InitializeCampaign<'info>>,
+            campaign_id: [u8; 32],
+            eligibility_root: [u8; 32],
+            zk_verifier_program: Pubkey,
+            pool_options: VotingPoolOptions,
+            winners_deadline: i64,
+            dispute_window_seconds: i64,
+        ) -> Result<()> {
+            campaign::initialize_campaign(
+                ctx,
+                campaign_id,
+                eligibility_root,
+                zk_verifier_program,
+                pool_options,
+                winners_deadline,
+                dispute_window_seconds,
+            )
+        }
+
+        pub fn set_campaign_eligibility<'info>(
+            ctx: Context<'_, '_, '_, 'info, UpdateCampaign<'info>>,
+            campaign_id: [u8; 32],
+            eligibility_root: [u8; 32],
+            eligible_count: u64,
+        ) -> Result<()> {
+            campaign::set_campaign_eligibility(ctx, campaign_id, eligibility_root, eligible_count)
+        }
+
+        pub fn commit_winners<'info>(
+            ctx: Context<'_, '_, '_, 'info, CommitWinners<'info>>,
+            campaign_id: [u8; 32],
+            winners_root: [u8; 32],
+            winner_count: u32,
+        ) -> Result<()> {
+            campaign::commit_winners(ctx, campaign_id, winners_root, winner_count)
+        }
+
+        pub fn open_dispute<'info>(
+            ctx: Context<'_, '_, '_, 'info, OpenDispute<'info>>,
+            campaign_id: [u8; 32],
+            nullifier_value: [u8; 32],
+            proof: Vec<u8>,
+            public_witness: Vec<u8>,
+        ) -> Result<()> {
+            campaign::open_dispute(ctx, campaign_id, nullifier_value, proof, public_witness)
+        }
+
+        #[allow(clippy::too_many_arguments)]
+        pub fn submit_evidence<'info>(
+            ctx: Context<'_, '_, '_, 'info, SubmitEvidence<'info>>,
+            campaign_id: [u8; 32],
+            content_hash: [u8; 32],
+            encrypted_key: Vec<u8>,
+            submitter: SubmitterClass,
+            nullifier_value: [u8; 32],
+            proof: Vec<u8>,
+            public_witness: Vec<u8>,
+        ) -> Result<()> {
+            evidence::submit_evidence(
+                ctx,
+                campaign_id,
+                content_hash,
+                encrypted_key,
+                submitter,
+                nullifier_value,
+                proof,
+                public_witness,
+            )
+        }
 
-    pub fn grant_analytics_access<'info>(
-        ctx: Context<'_, '_, '_, 'info, GrantAnalyticsAccess<'info>>,
-        campaign_id: [u8; 32],
-        allowed_address: Pubkey,
-    ) -> Result<()> {
-        analytics::grant_analytics_access(ctx, campaign_id, allowed_address)
+        pub fn grant_evidence_access<'info>(
+            ctx: Context<'_, '_, '_, 'info, GrantEvidenceAccess<'info>>,
+            campaign_id: [u8; 32],
+            nullifier_value: [u8; 32],
+            proof: Vec<u8>,
+            public_witness: Vec<u8>,
+        ) -> Result<()> {
+            evidence::grant_evidence_access(ctx, campaign_id, nullifier_value, proof, public_witness)
+        }
+
+        pub fn grant_outcome_access<'info>(
+            ctx: Context<'_, '_, '_, 'info, GrantOutcomeAccess<'info>>,
+            campaign_id: [u8; 32],
+            allowed_address: Pubkey,
+        ) -> Result<()> {
+            voting::grant_outcome_access(ctx, campaign_id, allowed_address)
+        }
+
+        pub fn finalize_outcome<'info>(
+            ctx: Context<'_, '_, '_, 'info, FinalizeOutcome<'info>>,
+            campaign_id: [u8; 32],
+            outcome_code: u128,
+        ) -> Result<()> {
+            voting::finalize_outcome(ctx, campaign_id, outcome_code)
+        }
+
+        pub fn execute_outcome<'info>(
+            ctx: Context<'_, '_, '_, 'info, ExecuteOutcome<'info>>,
+            campaign_id: [u8; 32],
+        ) -> Result<()> {
+            outcome_hook::execute_outcome(ctx, campaign_id)
+        }
+
+        pub fn initialize_escrow<'info>(
+            ctx: Context<'_, '_, '_, 'info, InitializeEscrow<'info>>,
+            campaign_id: [u8; 32],
+            refund_address: Pubkey,
+            payout_amount: u64,
+            max_claims: u32,
+            expires_at: i64,
+            winners_deadline: i64,
+        ) -> Result<()> {
+            escrow::initialize_escrow(
+                ctx,
+                campaign_id,
+                refund_address,
+                payout_amount,
+                max_claims,
+                expires_at,
+                winners_deadline,
+            )
+        }
+
+        pub fn initialize_token_escrow<'info>(
+            ctx: Context<'_, '_, '_, 'info, InitializeTokenEscrow<'info>>,
+            campaign_id: [u8; 32],
+            refund_address: Pubkey,
+            payout_amount: u64,
+            max_claims: u32,
+            expires_at: i64,
+            winners_deadline: i64,
+        ) -> Result<()> {
+            escrow::initialize_token_escrow(
+                ctx,
+                campaign_id,
+                refund_address,
+                payout_amount,
+                max_claims,
+                expires_at,
+                winners_deadline,
+            )
+        }
+
+        pub fn fund_escrow<'info>(
+            ctx: Context<'_, '_, '_, 'info, FundEscrow<'info>>,
+            campaign_id: [u8; 32],
+            amount: u64,
+        ) -> Result<()> {
+            escrow::fund_escrow(ctx, campaign_id, amount)
+        }
+
+        pub fn refund_escrow<'info>(
+            ctx: Context<'_, '_, '_, 'info, RefundEscrow<'info>>,
+            campaign_id: [u8; 32],
+        ) -> Result<()> {
+            escrow::refund_escrow(ctx, campaign_id)
+        }
+
+        pub fn set_claim_root<'info>(
+            ctx: Context<'_, '_, '_, 'info, UpdateEscrow<'info>>,
+            campaign_id: [u8; 32],
+            claim_root: [u8; 32],
+        ) -> Result<()> {
+            claim::set_claim_root(ctx, campaign_id, claim_root)
+        }
+
+        pub fn claim_zk<'info>(
+            ctx: Context<'_, '_, '_, 'info, ClaimZk<'info>>,
+            campaign_id: [u8; 32],
+            nullifier_value: [u8; 32],
+            proof: Vec<u8>,
+            public_witness: Vec<u8>,
+        ) -> Result<()> {
+            claim::claim_zk(ctx, campaign_id, nullifier_value, proof, public_witness)
+        }
+
+        pub fn record_claim<'info>(
+            ctx: Context<'_, '_, '_, 'info, RecordClaim<'info>>,
+            campaign_id: [u8; 32],
+            nullifier: [u8; 32],
+            max_claims: u32,
+        ) -> Result<()> {
+            claim::record_claim(ctx, campaign_id, nullifier, max_claims)
+        }
+
+        pub fn settle_equal_distribution<'info>(
+            ctx: Context<'_, '_, '_, 'info, SettleEqualDistribution<'info>>,
+            campaign_id: [u8; 32],
+        ) -> Result<()> {
+            settlement::settle_equal_distribution(ctx, campaign_id)
+        }
+
+        pub fn grant_public_access<'info>(
+            ctx: Context<'_, '_, '_, 'info, GrantPublicAccess<'info>>,
+            campaign_id: [u8; 32],
+        ) -> Result<()> {
+            voting::grant_public_access(ctx, campaign_id)
+        }
+
+        pub fn set_eligibility_root<'info>(
+            ctx: Context<'_, '_, '_, 'info, SetEligibilityRoot<'info>>,
+            campaign_id: [u8; 32],
+            eligibility_root: [u8; 32],
+            eligible_count: u64,
+        ) -> Result<()> {
+            voting::set_eligibility_root(ctx, campaign_id, eligibility_root, eligible_count)
+        }
+
+        pub fn migrate_voting_pool<'info>(
+            ctx: Context<'_, '_, '_, 'info, MigrateVotingPool<'info>>,
+            campaign_id: [u8; 32],
+        ) -> Result<()> {
+            voting::migrate_voting_pool(ctx, campaign_id)
+        }
+
+        pub fn initialize_analytics<'info>(
+            ctx: Context<'_, '_, '_, 'info, InitializeAnalytics<'info>>,
+            campaign_id: [u8; 32],
+        ) -> Result<()> {
+            analytics::initialize_analytics(ctx, campaign_id)
+        }
+
+        pub fn track_event<'info>(
+            ctx: Context<'_, '_, '_, 'info, TrackEvent<'info>>,
+            campaign_id: [u8; 32],
+            encrypted_increment: Vec<u8>,
+            event_type: u8,
+        ) -> Result<()> {
+            analytics::track_event(ctx, campaign_id, encrypted_increment, event_type)
+        }
+
+        pub fn grant_analytics_access<'info>(
+            ctx: Context<'_, '_, '_, 'info, GrantAnalyticsAccess<'info>>,
+            campaign_id: [u8; 32],
+            allowed_address: Pubkey,
+        ) -> Result<()> {
+            analytics::grant_analytics_access(ctx, campaign_id, allowed_address)
+        }
+
+        pub fn migrate_analytics<'info>(
+            ctx: Context<'_, '_, '_, 'info, MigrateAnalytics<'info>>,
+            campaign_id: [u8; 32],
+        ) -> Result<()> {
+            analytics::migrate_analytics(ctx, campaign_id)
+        }
     }
 }
 
+pub use interface::*;
+
 #[error_code]
 pub enum ErrorCode {
     #[msg("Unauthorized")]
@@ -102,4 +454,84 @@ pub enum ErrorCode {
     InvalidAllowedAddress,
     #[msg("Invalid poseidon input")]
     InvalidPoseidonInput,
+    #[msg("Account already migrated")]
+    AlreadyMigrated,
+    #[msg("Missing allowance account")]
+    MissingAllowanceAccount,
+    #[msg("Voting still active")]
+    VotingStillActive,
+    #[msg("Invalid voting pool options")]
+    InvalidPoolOptions,
+    #[msg("Voting void: insufficient anonymity set")]
+    VotingVoid,
+    #[msg("Invalid decryptor accounts")]
+    InvalidDecryptorAccounts,
+    #[msg("Public reveal disabled")]
+    PublicRevealDisabled,
+    #[msg("Invalid vote weight")]
+    InvalidVoteWeight,
+    #[msg("Revoting disabled")]
+    RevoteDisabled,
+    #[msg("Ballot sequence must increase")]
+    StaleBallotSequence,
+    #[msg("Delegation unavailable for this pool")]
+    DelegationUnavailable,
+    #[msg("Invalid delegation")]
+    InvalidDelegation,
+    #[msg("Early decision unavailable")]
+    EarlyDecisionUnavailable,
+    #[msg("Invalid decryption attestation")]
+    InvalidDecryptionAttestation,
+    #[msg("Voting window closed")]
+    VotingWindowClosed,
+    #[msg("Appeal unavailable")]
+    AppealUnavailable,
+    #[msg("Invalid appeal round")]
+    InvalidAppealRound,
+    #[msg("Jury selection unavailable for this pool")]
+    JuryUnavailable,
+    #[msg("Jury already drawn")]
+    JuryAlreadyDrawn,
+    #[msg("Jury seed does not match its commitment")]
+    InvalidJurySeed,
+    #[msg("Slot hash for the jury draw is unavailable")]
+    JurySlotHashUnavailable,
+    #[msg("Dispute cannot be opened for this campaign")]
+    DisputeUnavailable,
+    #[msg("Winners deadline has passed")]
+    WinnersDeadlinePassed,
+    #[msg("Winners deadline has not passed")]
+    WinnersDeadlineNotPassed,
+    #[msg("Evidence log full for this submitter")]
+    EvidenceLogFull,
+    #[msg("Outcome unavailable")]
+    OutcomeUnavailable,
+    #[msg("Invalid outcome hook")]
+    InvalidOutcomeHook,
+    #[msg("Outcome already executed")]
+    OutcomeAlreadyExecuted,
+    #[msg("Invalid escrow terms")]
+    InvalidEscrowTerms,
+    #[msg("Escrow closed")]
+    EscrowClosed,
+    #[msg("Escrow release conditions not met")]
+    EscrowReleaseLocked,
+    #[msg("Insufficient escrow balance")]
+    InsufficientEscrowBalance,
+    #[msg("Token accounts do not match the escrow vault")]
+    InvalidEscrowVault,
+    #[msg("Claims unavailable for this escrow")]
+    ClaimUnavailable,
+    #[msg("Maximum claims reached")]
+    ClaimLimitReached,
+    #[msg("Proof is bound to a different recipient")]
+    RecipientMismatch,
+    #[msg("Winners already committed")]
+    WinnersAlreadyCommitted,
+    #[msg("Invalid winners")]
+    InvalidWinners,
+    #[msg("Eligibility is frozen by a jury draw")]
+    EligibilityFrozen,
+    #[msg("Jury draw pending")]
+    JuryDrawPending,
 }
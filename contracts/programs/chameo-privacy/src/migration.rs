@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+
+/// Decodes an account written by an older version of the program.
+///
+/// Fields are only ever appended, so zero-padding the stored bytes up to the
+/// current size yields the current layout with every new field defaulted.
+pub(crate) fn read_padded<T: AccountDeserialize>(info: &AccountInfo, space: usize) -> Result<T> {
+    let data = info.try_borrow_data()?;
+    let mut padded = vec![0u8; core::cmp::max(data.len(), 8 + space)];
+    padded[..data.len()].copy_from_slice(&data);
+    T::try_deserialize(&mut padded.as_slice())
+}
+
+/// Grows an account to `new_len`, topping up rent from `payer`.
+pub(crate) fn resize_account<'info>(
+    info: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    new_len: usize,
+) -> Result<()> {
+    let required = Rent::get()?.minimum_balance(new_len);
+    let current = info.lamports();
+    if required > current {
+        let cpi_ctx = CpiContext::new(
+            system_program.clone(),
+            Transfer {
+                from: payer.clone(),
+                to: info.clone(),
+            },
+        );
+        transfer(cpi_ctx, required - current)?;
+    }
+    info.resize(new_len)?;
+    Ok(())
+}
+
+pub(crate) fn write_account<T: AccountSerialize>(info: &AccountInfo, account: &T) -> Result<()> {
+    let mut data = info.try_borrow_mut_data()?;
+    let mut writer: &mut [u8] = &mut data;
+    account.try_serialize(&mut writer)
+}
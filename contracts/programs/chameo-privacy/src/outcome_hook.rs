@@ -0,0 +1,92 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::invoke_signed;
+use crate::ErrorCode;
+use crate::events::{OutcomeExecuted, EVENT_SCHEMA_VERSION};
+use crate::voting::{VoteOutcome, VotingPool};
+
+#[derive(Accounts)]
+#[instruction(campaign_id: [u8; 32])]
+pub struct ExecuteOutcome<'info> {
+    #[account(
+        mut,
+        seeds = [b"voting_pool", campaign_id.as_ref()],
+        bump
+    )]
+    pub voting_pool: Account<'info, VotingPool>,
+    /// CHECK: Outcome hook the pool registered at creation
+    #[account(
+        executable,
+        address = voting_pool.outcome_hook_program @ ErrorCode::InvalidOutcomeHook
+    )]
+    pub hook_program: AccountInfo<'info>,
+    pub requester: Signer<'info>,
+}
+
+/// Calls the pool's outcome hook once, after its final round has an outcome
+/// that can no longer be appealed.
+///
+/// The hook receives `discriminator || campaign_id || outcome` with the pool as
+/// its first account, signed by the pool PDA, followed by `remaining_accounts`
+/// as passed.
+pub(crate) fn execute_outcome<'info>(
+    ctx: Context<'_, '_, '_, 'info, ExecuteOutcome<'info>>,
+    campaign_id: [u8; 32],
+) -> Result<()> {
+    let voting_pool = &mut ctx.accounts.voting_pool;
+    require!(!voting_pool.is_active, ErrorCode::VotingStillActive);
+    require!(voting_pool.is_final_round(), ErrorCode::OutcomeUnavailable);
+    require!(
+        voting_pool.outcome != VoteOutcome::Pending,
+        ErrorCode::OutcomeUnavailable
+    );
+    require!(
+        voting_pool.outcome_hook_program != Pubkey::default(),
+        ErrorCode::InvalidOutcomeHook
+    );
+    // Anyone may call this, so it waits out the appeal window rather than ending it.
+    require!(
+        !voting_pool.appeal_open(Clock::get()?.unix_timestamp),
+        ErrorCode::OutcomeUnavailable
+    );
+    require!(!voting_pool.outcome_executed, ErrorCode::OutcomeAlreadyExecuted);
+    voting_pool.outcome_executed = true;
+
+    let outcome = voting_pool.outcome;
+    let mut data = voting_pool.outcome_hook_discriminator.to_vec();
+    data.extend_from_slice(&campaign_id);
+    outcome.serialize(&mut data)?;
+
+    let pool_info = voting_pool.to_account_info();
+    let mut accounts = vec![AccountMeta::new_readonly(pool_info.key(), true)];
+    let mut account_infos = vec![pool_info];
+    for account in ctx.remaining_accounts {
+        accounts.push(if account.is_writable {
+            AccountMeta::new(account.key(), account.is_signer)
+        } else {
+            AccountMeta::new_readonly(account.key(), account.is_signer)
+        });
+        account_infos.push(account.clone());
+    }
+    account_infos.push(ctx.accounts.hook_program.clone());
+
+    let hook = Instruction {
+        program_id: ctx.accounts.hook_program.key(),
+        accounts,
+        data,
+    };
+    let bump = [ctx.bumps.voting_pool];
+    let seeds: &[&[u8]] = &[b"voting_pool", campaign_id.as_ref(), &bump];
+    invoke_signed(&hook, &account_infos, &[seeds])?;
+
+    emit!(OutcomeExecuted {
+        schema_version: EVENT_SCHEMA_VERSION,
+        campaign_id,
+        voting_pool: ctx.accounts.voting_pool.key(),
+        outcome,
+        hook_program: ctx.accounts.hook_program.key(),
+        requester: ctx.accounts.requester.key(),
+    });
+
+    Ok(())
+}
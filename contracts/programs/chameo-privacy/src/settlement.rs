@@ -0,0 +1,125 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::ErrorCode;
+use crate::campaign::Campaign;
+use crate::claim::CLAIM_VERIFIER_PROGRAM_ID;
+use crate::escrow::{dispute_outcome, escrow_balance, release_funds, Escrow};
+use crate::events::{EqualDistributionSettled, EVENT_SCHEMA_VERSION};
+use crate::voting::{VoteOutcome, VotingPool};
+
+#[derive(Accounts)]
+#[instruction(campaign_id: [u8; 32])]
+pub struct SettleEqualDistribution<'info> {
+    #[account(
+        mut,
+        seeds = [b"escrow", campaign_id.as_ref()],
+        bump
+    )]
+    pub escrow: Account<'info, Escrow>,
+    #[account(
+        seeds = [b"campaign", campaign_id.as_ref()],
+        bump
+    )]
+    pub campaign: Account<'info, Campaign>,
+    /// Final round of the campaign's dispute.
+    pub voting_pool: Account<'info, VotingPool>,
+    /// CHECK: Refund destination recorded on the escrow, paid the dust
+    #[account(
+        mut,
+        address = escrow.refund_address @ ErrorCode::Unauthorized
+    )]
+    pub refund_address: AccountInfo<'info>,
+    /// The refund address's token account, for SPL escrows.
+    #[account(
+        mut,
+        constraint = refund_token_account.owner == escrow.refund_address @ ErrorCode::Unauthorized
+    )]
+    pub refund_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub requester: Signer<'info>,
+    #[account(mut, address = escrow.vault @ ErrorCode::InvalidEscrowVault)]
+    pub vault: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(address = escrow.mint @ ErrorCode::InvalidEscrowVault)]
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+    #[account(address = escrow.token_program @ ErrorCode::InvalidEscrowVault)]
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+}
+
+/// Splits the vault evenly across the campaign's eligible leaves once its
+/// dispute has finalized on `EqualDistribution`. Anyone may call it.
+///
+/// Each share is `balance / eligible_count`. The remainder is paid to the
+/// refund address here, so the shares always add up to what the vault holds.
+/// Every leaf of the eligibility root can then claim one share with
+/// `claim_zk` until the escrow expires.
+pub(crate) fn settle_equal_distribution<'info>(
+    ctx: Context<'_, '_, '_, 'info, SettleEqualDistribution<'info>>,
+    campaign_id: [u8; 32],
+) -> Result<()> {
+    let accounts = &ctx.accounts;
+    let escrow = &accounts.escrow;
+    require!(!escrow.is_closed, ErrorCode::EscrowClosed);
+    require!(
+        escrow.claim_count == 0 && !escrow.equal_distribution_settled,
+        ErrorCode::ClaimUnavailable
+    );
+    require!(
+        CLAIM_VERIFIER_PROGRAM_ID != Pubkey::default(),
+        ErrorCode::ClaimUnavailable
+    );
+    // Only a dispute opened for missing winners may split the vault; its pool
+    // comes from `open_dispute`, since no other pool can exist for the campaign.
+    let campaign = &accounts.campaign;
+    require!(
+        campaign.dispute_opened && campaign.winners_root == [0u8; 32],
+        ErrorCode::DisputeUnavailable
+    );
+    require!(
+        dispute_outcome(escrow, &accounts.voting_pool, &campaign_id)? == VoteOutcome::EqualDistribution,
+        ErrorCode::EscrowReleaseLocked
+    );
+
+    let eligible_count = campaign.pool_options.eligible_count;
+    require!(
+        eligible_count > 0 && eligible_count <= u32::MAX as u64,
+        ErrorCode::ClaimUnavailable
+    );
+
+    let tokens = escrow.tokens(&accounts.vault, &accounts.mint, &accounts.token_program)?;
+    let balance = escrow_balance(escrow, tokens.as_ref())?;
+    let share = balance / eligible_count;
+    let dust = balance % eligible_count;
+    require!(share > 0, ErrorCode::InsufficientEscrowBalance);
+
+    if dust > 0 {
+        let destination = match &tokens {
+            Some(_) => accounts
+                .refund_token_account
+                .as_ref()
+                .ok_or(ErrorCode::InvalidEscrowVault)?
+                .to_account_info(),
+            None => accounts.refund_address.clone(),
+        };
+        release_funds(escrow, ctx.bumps.escrow, tokens.as_ref(), &destination, dust)?;
+    }
+
+    let claim_root = campaign.eligibility_root;
+    let escrow = &mut ctx.accounts.escrow;
+    escrow.payout_amount = share;
+    escrow.max_claims = eligible_count as u32;
+    escrow.claim_root = claim_root;
+    escrow.released = escrow.released.saturating_add(dust);
+    escrow.equal_distribution_settled = true;
+
+    emit!(EqualDistributionSettled {
+        schema_version: EVENT_SCHEMA_VERSION,
+        campaign_id,
+        escrow: escrow.key(),
+        voting_pool: ctx.accounts.voting_pool.key(),
+        claim_root,
+        eligible_count: eligible_count as u32,
+        share,
+        dust,
+    });
+
+    Ok(())
+}
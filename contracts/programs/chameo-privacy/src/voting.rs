@@ -1,42 +1,352 @@
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::instruction::Instruction;
 use anchor_lang::solana_program::program::invoke;
+use anchor_lang::solana_program::sysvar::instructions::ID as INSTRUCTIONS_SYSVAR_ID;
 use solana_poseidon::{hashv as poseidon_hashv, Endianness, Parameters};
 use inco_lightning::cpi::accounts::{Operation, Allow};
-use inco_lightning::cpi::{new_euint128, as_euint128, e_add, e_eq, e_select, allow};
+use inco_lightning::cpi::{
+    new_euint128, as_euint128, e_add, e_sub, e_mul, e_and, e_shr, e_eq, e_ge, e_le, e_lt, e_select, allow,
+};
 use inco_lightning::types::Euint128;
 use inco_lightning::ID as INCO_LIGHTNING_ID;
 use crate::ErrorCode;
+use crate::attestation;
+use crate::events::{
+    AccountMigrated, EligibilityRootUpdated, OutcomeAccessGranted, OutcomeFinalized, PublicAccessGranted, VoteCast,
+    VoteRecast, VotingClosed, VotingPoolInitialized, VotingVoided, EVENT_SCHEMA_VERSION,
+};
+use crate::delegation::Delegation;
+use crate::handle::IncoHandle;
+use crate::migration;
 
-const ZK_PROOF_LEN: usize = 388;
-const ZK_PUBLIC_WITNESS_LEN: usize = 12 + 32 + 32 + 32;
+pub(crate) const ZK_PROOF_LEN: usize = 388;
+const ZK_WITNESS_HEADER_LEN: usize = 12;
+const ZK_FIELD_LEN: usize = 32;
+// Public inputs: merkle_root, nullifier, commitment, then weight for weighted pools
+// or the jury root once a jury is drawn.
+const ZK_PUBLIC_INPUTS: usize = 3;
+const ZK_WEIGHTED_PUBLIC_INPUTS: usize = 4;
+const ZK_JURY_PUBLIC_INPUTS: usize = 4;
 const CIPHERTEXT_LEN: usize = 114;
+// Ballot commitments cover the ciphertext plus metadata, padded to 8 Poseidon limbs.
+const BALLOT_META_LEN: usize = 14;
+const BALLOT_LEN: usize = CIPHERTEXT_LEN + BALLOT_META_LEN;
+const BALLOT_KIND_DELEGATION: u8 = 1;
+const BALLOT_KIND_DISPUTE: u8 = 2;
+const BALLOT_KIND_EVIDENCE: u8 = 3;
+const BALLOT_KIND_EVIDENCE_ACCESS: u8 = 4;
 const POSEIDON_CHUNK_LEN: usize = 16;
+/// Most options an approval or quadratic ballot can cover.
+pub const MAX_BALLOT_OPTIONS: usize = 8;
+/// Questions a multi-question ballot answers, fixed by the
+/// `multi_question_vote_eligibility` circuit's `QUESTIONS`.
+pub const MAX_QUESTIONS: usize = 2;
+/// How long after a round closes it may be appealed.
+pub const APPEAL_WINDOW_SECONDS: i64 = 3 * 24 * 60 * 60;
+/// Bits per option in a quadratic ballot's packed allocation.
+pub const QUADRATIC_LANE_BITS: u32 = 16;
 
+/// Plaintext values of `VotingPool::encrypted_outcome` once decrypted.
+pub const OUTCOME_REFUND_HOST: u128 = 0;
+pub const OUTCOME_EQUAL_DISTRIBUTION: u128 = 1;
+pub const OUTCOME_TIE: u128 = 2;
+
+// New fields must be appended so `migrate_voting_pool` can zero-extend older accounts.
 #[account]
+#[derive(InitSpace, Default)]
 pub struct VotingPool {
+    pub version: u8,
     pub campaign_id: [u8; 32],
     pub authority: Pubkey,
     pub eligibility_root: [u8; 32],
     pub zk_verifier_program: Pubkey,
-    pub refund_host_votes: Euint128,
-    pub equal_distribution_votes: Euint128,
+    pub refund_host_votes: IncoHandle,
+    pub equal_distribution_votes: IncoHandle,
     pub total_votes: u64,
     pub is_active: bool,
+    pub encrypt_turnout: bool,
+    pub encrypted_total_votes: IncoHandle,
+    pub has_votes: bool,
+    pub reveal_mode: RevealMode,
+    pub encrypted_outcome: IncoHandle,
+    pub min_votes: u64,
+    pub is_void: bool,
+    pub outcome: VoteOutcome,
+    pub public_reveal: bool,
+    pub weighted: bool,
+    pub allow_revote: bool,
+    pub ballot_type: BallotType,
+    pub option_count: u8,
+    /// Per-option tallies for approval and quadratic pools; empty for single choice.
+    #[max_len(MAX_BALLOT_OPTIONS)]
+    pub option_tallies: Vec<IncoHandle>,
+    pub question_count: u8,
+    /// Choice 0 and choice 1 tallies for each question after the first, which
+    /// uses the refund/equal tallies.
+    #[max_len(2 * (MAX_QUESTIONS - 1))]
+    pub question_tallies: Vec<IncoHandle>,
+    /// Eligible voters under the current root, or their total weight in weighted pools.
+    pub eligible_count: u64,
+    /// Plaintext weight cast so far; not tracked when turnout is encrypted.
+    pub cast_weight: u64,
+    /// Encrypted 1 if the leader can no longer be overtaken, from `check_early_decision`.
+    pub early_decision: IncoHandle,
+    pub decided_early: bool,
+    /// Lamports an appellant posts to open the next round.
+    pub appeal_bond: u64,
+    pub max_appeal_rounds: u8,
+    /// 0 for the original vote; appeal rounds count up from 1.
+    pub round: u8,
+    /// Round this one appeals, or the default key for the original vote.
+    pub parent_pool: Pubkey,
+    /// Round appealing this one, or the default key while it is the last round.
+    pub appeal_pool: Pubkey,
+    pub appellant: Pubkey,
+    /// Unix time after which ballots are rejected; 0 leaves the window to the authority.
+    pub voting_ends_at: i64,
+    /// Root over the drawn jurors' leaf indices, or zero when every leaf may vote.
+    pub jury_root: [u8; 32],
+    pub outcome_policy: OutcomePolicy,
+    pub outcome_hook_program: Pubkey,
+    pub outcome_hook_discriminator: [u8; 8],
+    pub outcome_executed: bool,
+    /// Unix time the round was closed, which starts its appeal window.
+    pub closed_at: i64,
+    /// Verifier for jurors' proofs once a jury is drawn. `zk_verifier_program`
+    /// keeps the pool's own, which appeal rounds inherit.
+    pub jury_verifier_program: Pubkey,
+    /// Set while a committed jury seed awaits its draw, which ballots wait for.
+    pub jury_pending: bool,
 }
 
 impl VotingPool {
-    pub const LEN: usize = 32 + 32 + 32 + 32 + 16 + 16 + 8 + 1;
+    pub const VERSION: u8 = 1;
+
+    /// Whether this round's result stands, i.e. no appeal has followed it.
+    pub fn is_final_round(&self) -> bool {
+        self.appeal_pool == Pubkey::default()
+    }
+
+    /// Whether this closed round may still be appealed at `now`.
+    pub fn appeal_open(&self, now: i64) -> bool {
+        self.is_final_round()
+            && self.round < self.max_appeal_rounds
+            && now <= self.closed_at.saturating_add(APPEAL_WINDOW_SECONDS)
+    }
+
+    /// Options that recreate this pool's ballot and reveal settings.
+    pub fn options(&self) -> VotingPoolOptions {
+        VotingPoolOptions {
+            encrypt_turnout: self.encrypt_turnout,
+            reveal_mode: self.reveal_mode,
+            min_votes: self.min_votes,
+            public_reveal: self.public_reveal,
+            weighted: self.weighted,
+            allow_revote: self.allow_revote,
+            ballot_type: self.ballot_type,
+            option_count: self.option_count,
+            question_count: self.question_count,
+            eligible_count: self.eligible_count,
+            appeal_bond: self.appeal_bond,
+            max_appeal_rounds: self.max_appeal_rounds,
+            outcome_policy: self.outcome_policy,
+            outcome_hook_program: self.outcome_hook_program,
+            outcome_hook_discriminator: self.outcome_hook_discriminator,
+        }
+    }
+
+    /// Plaintext turnout, or `None` when the pool keeps it encrypted.
+    pub fn public_total_votes(&self) -> Option<u64> {
+        if self.encrypt_turnout {
+            None
+        } else {
+            Some(self.total_votes)
+        }
+    }
+
+    /// Whether only drawn jurors may vote.
+    pub fn has_jury(&self) -> bool {
+        self.jury_root != [0u8; 32]
+    }
+
+    /// Verifier ballots must currently be proven against.
+    pub fn ballot_verifier(&self) -> Pubkey {
+        if self.has_jury() {
+            self.jury_verifier_program
+        } else {
+            self.zk_verifier_program
+        }
+    }
+
+    pub fn public_witness_len(&self) -> usize {
+        let inputs = if self.weighted {
+            ZK_WEIGHTED_PUBLIC_INPUTS
+        } else if self.has_jury() {
+            ZK_JURY_PUBLIC_INPUTS
+        } else {
+            ZK_PUBLIC_INPUTS
+        };
+        public_witness_len(inputs)
+    }
+
+    /// Handles a decryptor is allowed to read once the pool has closed.
+    pub fn revealed_handles(&self) -> Vec<u128> {
+        match self.reveal_mode {
+            RevealMode::OutcomeOnly => vec![self.encrypted_outcome.0],
+            RevealMode::Tallies => {
+                let mut handles = match self.ballot_type {
+                    BallotType::SingleChoice => {
+                        let mut handles = vec![self.refund_host_votes.0, self.equal_distribution_votes.0];
+                        handles.extend(self.question_tallies.iter().map(|tally| tally.0));
+                        handles
+                    }
+                    BallotType::Approval | BallotType::Quadratic => {
+                        self.option_tallies.iter().map(|tally| tally.0).collect()
+                    }
+                };
+                if self.encrypt_turnout {
+                    handles.push(self.encrypted_total_votes.0);
+                }
+                handles
+            }
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RevealMode {
+    /// Decryptors see both raw tallies.
+    #[default]
+    Tallies,
+    /// Decryptors only see the encrypted outcome computed at close.
+    OutcomeOnly,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BallotType {
+    /// The ciphertext encrypts one choice: 0 refunds the host, 1 splits equally.
+    #[default]
+    SingleChoice,
+    /// The ciphertext encrypts a bitmask with bit `i` approving option `i`.
+    Approval,
+    /// The ciphertext packs votes per option into `QUADRATIC_LANE_BITS` lanes,
+    /// costing the sum of their squares against the leaf's voice-credit budget.
+    Quadratic,
+}
+
+/// Plaintext result recorded on the pool.
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum VoteOutcome {
+    #[default]
+    Pending,
+    RefundHost,
+    EqualDistribution,
+    Tie,
+}
+
+/// Default outcome when a pool closes below its anonymity threshold or quorum.
+pub const VOID_DEFAULT_OUTCOME: VoteOutcome = VoteOutcome::RefundHost;
+const QUORUM_BPS_DENOMINATOR: u64 = 10_000;
+
+/// How a single-choice pool's tallies map to its outcome, fixed at creation.
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OutcomePolicy {
+    /// Outcome when neither side leads by `min_margin`; `Tie` leaves it to the host.
+    pub tie_break: VoteOutcome,
+    /// Lead a side needs to win outright; 0 and 1 both mean any lead.
+    pub min_margin: u64,
+    /// Share of `eligible_count` that must be cast, in basis points; 0 disables the quorum.
+    pub quorum_bps: u16,
+    /// Outcome when quorum fails or the pool is void.
+    pub quorum_default: VoteOutcome,
+}
+
+impl Default for OutcomePolicy {
+    fn default() -> Self {
+        Self {
+            tie_break: VoteOutcome::Tie,
+            min_margin: 0,
+            quorum_bps: 0,
+            quorum_default: VOID_DEFAULT_OUTCOME,
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Default)]
+pub struct VotingPoolOptions {
+    /// Keep turnout as an encrypted counter revealed only with the tallies.
+    pub encrypt_turnout: bool,
+    pub reveal_mode: RevealMode,
+    /// Smallest turnout at which results may be decrypted; 0 disables the check.
+    /// Needs plaintext turnout, so it can't be combined with `encrypt_turnout`.
+    pub min_votes: u64,
+    /// Let anyone request decryption of the revealed handles once the pool closes.
+    pub public_reveal: bool,
+    /// Leaves commit to a vote weight proven by `weighted_vote_eligibility`.
+    pub weighted: bool,
+    /// Let voters replace their ballot with `recast_vote_zk` until close.
+    pub allow_revote: bool,
+    pub ballot_type: BallotType,
+    /// Options on an approval or quadratic ballot, up to `MAX_BALLOT_OPTIONS`; 0 for single choice.
+    pub option_count: u8,
+    /// Single-choice questions answered by each ballot: 1, or `MAX_QUESTIONS`
+    /// with the `multi_question_vote_eligibility` circuit. 0 means 1.
+    pub question_count: u8,
+    /// Eligible voters (or total leaf weight) under the initial root; 0 disables
+    /// `check_early_decision`.
+    pub eligible_count: u64,
+    /// Lamports an appellant posts with `appeal_voting`.
+    pub appeal_bond: u64,
+    /// Appeal rounds allowed after the original vote; 0 disables appeals.
+    pub max_appeal_rounds: u8,
+    pub outcome_policy: OutcomePolicy,
+    /// Program `execute_outcome` calls once the outcome is final; the default key disables it.
+    pub outcome_hook_program: Pubkey,
+    /// Leads the hook's instruction data, e.g. an Anchor discriminator.
+    pub outcome_hook_discriminator: [u8; 8],
+}
+
+/// Pool layout from before accounts carried a version byte.
+#[derive(AnchorDeserialize)]
+struct LegacyVotingPool {
+    campaign_id: [u8; 32],
+    authority: Pubkey,
+    eligibility_root: [u8; 32],
+    zk_verifier_program: Pubkey,
+    refund_host_votes: IncoHandle,
+    equal_distribution_votes: IncoHandle,
+    total_votes: u64,
+    is_active: bool,
+}
+
+impl LegacyVotingPool {
+    const LEN: usize = 32 + 32 + 32 + 32 + 16 + 16 + 8 + 1;
+
+    fn into_current(self) -> VotingPool {
+        VotingPool {
+            version: 0,
+            campaign_id: self.campaign_id,
+            authority: self.authority,
+            eligibility_root: self.eligibility_root,
+            zk_verifier_program: self.zk_verifier_program,
+            refund_host_votes: self.refund_host_votes,
+            equal_distribution_votes: self.equal_distribution_votes,
+            total_votes: self.total_votes,
+            is_active: self.is_active,
+            ..Default::default()
+        }
+    }
 }
 
 #[account]
+#[derive(InitSpace)]
 pub struct Nullifier {
     pub campaign_id: [u8; 32],
     pub value: [u8; 32],
-}
-
-impl Nullifier {
-    pub const LEN: usize = 32 + 32;
+    // Only tracked in revote pools, so a replacement ballot can undo this one.
+    pub sequence: u64,
+    pub refund_contribution: IncoHandle,
+    pub equal_contribution: IncoHandle,
 }
 
 #[derive(Accounts)]
@@ -45,11 +355,17 @@ pub struct InitializeVotingPool<'info> {
     #[account(
         init,
         payer = authority,
-        space = 8 + VotingPool::LEN,
+        space = 8 + VotingPool::INIT_SPACE,
         seeds = [b"voting_pool", campaign_id.as_ref()],
         bump
     )]
     pub voting_pool: Account<'info, VotingPool>,
+    /// CHECK: The campaign's on-chain terms address, which must be empty
+    #[account(
+        seeds = [b"campaign", campaign_id.as_ref()],
+        bump
+    )]
+    pub campaign: UncheckedAccount<'info>,
     #[account(mut)]
     pub authority: Signer<'info>,
     /// CHECK: Inco Lightning program
@@ -64,7 +380,7 @@ pub struct CastVoteZk<'info> {
     #[account(
         init,
         payer = relayer,
-        space = 8 + Nullifier::LEN,
+        space = 8 + Nullifier::INIT_SPACE,
         seeds = [b"nullifier", campaign_id.as_ref(), nullifier_value.as_ref()],
         bump
     )]
@@ -75,6 +391,12 @@ pub struct CastVoteZk<'info> {
         bump
     )]
     pub voting_pool: Account<'info, VotingPool>,
+    /// Weight delegated to this voter, if any
+    #[account(
+        seeds = [b"delegation", campaign_id.as_ref(), nullifier_value.as_ref()],
+        bump
+    )]
+    pub delegation: Option<Account<'info, Delegation>>,
     #[account(mut)]
     pub relayer: Signer<'info>,
     /// CHECK: ZK verifier program
@@ -85,6 +407,30 @@ pub struct CastVoteZk<'info> {
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+#[instruction(campaign_id: [u8; 32], nullifier_value: [u8; 32])]
+pub struct RecastVoteZk<'info> {
+    #[account(
+        mut,
+        seeds = [b"nullifier", campaign_id.as_ref(), nullifier_value.as_ref()],
+        bump
+    )]
+    pub nullifier: Account<'info, Nullifier>,
+    #[account(
+        mut,
+        seeds = [b"voting_pool", campaign_id.as_ref()],
+        bump
+    )]
+    pub voting_pool: Account<'info, VotingPool>,
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+    /// CHECK: ZK verifier program
+    pub zk_verifier_program: AccountInfo<'info>,
+    /// CHECK: Inco Lightning program
+    #[account(address = INCO_LIGHTNING_ID)]
+    pub inco_lightning_program: AccountInfo<'info>,
+}
+
 #[derive(Accounts)]
 #[instruction(campaign_id: [u8; 32])]
 pub struct CloseVoting<'info> {
@@ -99,12 +445,71 @@ pub struct CloseVoting<'info> {
     pub authority: Signer<'info>,
     /// CHECK: Address granted decryption access
     pub allowed_address: UncheckedAccount<'info>,
-    /// CHECK: Allowance account for refund votes
+    /// CHECK: Allowance account for refund votes, required for single-choice tallies
     #[account(mut)]
-    pub allowance_refund: AccountInfo<'info>,
-    /// CHECK: Allowance account for equal dist votes
+    pub allowance_refund: Option<AccountInfo<'info>>,
+    /// CHECK: Allowance account for equal dist votes, required for single-choice tallies
     #[account(mut)]
-    pub allowance_equal: AccountInfo<'info>,
+    pub allowance_equal: Option<AccountInfo<'info>>,
+    /// CHECK: Allowance account for encrypted turnout, required when the pool encrypts it
+    #[account(mut)]
+    pub allowance_turnout: Option<AccountInfo<'info>>,
+    /// CHECK: Inco Lightning program
+    #[account(address = INCO_LIGHTNING_ID)]
+    pub inco_lightning_program: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(campaign_id: [u8; 32])]
+pub struct GrantOutcomeAccess<'info> {
+    #[account(
+        seeds = [b"voting_pool", campaign_id.as_ref()],
+        bump,
+        constraint = voting_pool.authority == authority.key() @ ErrorCode::Unauthorized
+    )]
+    pub voting_pool: Account<'info, VotingPool>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// CHECK: Address granted decryption access
+    pub allowed_address: UncheckedAccount<'info>,
+    /// CHECK: Allowance account for the encrypted outcome
+    #[account(mut)]
+    pub allowance_outcome: AccountInfo<'info>,
+    /// CHECK: Inco Lightning program
+    #[account(address = INCO_LIGHTNING_ID)]
+    pub inco_lightning_program: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(campaign_id: [u8; 32])]
+pub struct FinalizeOutcome<'info> {
+    #[account(
+        mut,
+        seeds = [b"voting_pool", campaign_id.as_ref()],
+        bump
+    )]
+    pub voting_pool: Account<'info, VotingPool>,
+    pub requester: Signer<'info>,
+    /// CHECK: Instructions sysvar holding the attested-decrypt signature
+    #[account(address = INSTRUCTIONS_SYSVAR_ID)]
+    pub instructions: AccountInfo<'info>,
+    /// CHECK: Inco Lightning program
+    #[account(address = INCO_LIGHTNING_ID)]
+    pub inco_lightning_program: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(campaign_id: [u8; 32])]
+pub struct GrantPublicAccess<'info> {
+    #[account(
+        seeds = [b"voting_pool", campaign_id.as_ref()],
+        bump
+    )]
+    pub voting_pool: Account<'info, VotingPool>,
+    #[account(mut)]
+    pub requester: Signer<'info>,
     /// CHECK: Inco Lightning program
     #[account(address = INCO_LIGHTNING_ID)]
     pub inco_lightning_program: AccountInfo<'info>,
@@ -121,46 +526,241 @@ pub struct SetEligibilityRoot<'info> {
         constraint = voting_pool.authority == authority.key() @ ErrorCode::Unauthorized
     )]
     pub voting_pool: Account<'info, VotingPool>,
+    /// CHECK: The pool's jury address, which must not hold a committed draw
+    #[account(
+        seeds = [b"jury", campaign_id.as_ref()],
+        bump
+    )]
+    pub jury: UncheckedAccount<'info>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(campaign_id: [u8; 32])]
+pub struct MigrateVotingPool<'info> {
+    /// CHECK: Decoded by hand because older layouts don't deserialize as `VotingPool`
+    #[account(
+        mut,
+        seeds = [b"voting_pool", campaign_id.as_ref()],
+        bump,
+        owner = crate::ID
+    )]
+    pub voting_pool: UncheckedAccount<'info>,
+    #[account(mut)]
     pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
 }
 
-pub fn initialize_voting_pool<'info>(
+pub(crate) fn initialize_voting_pool<'info>(
     ctx: Context<'_, '_, '_, 'info, InitializeVotingPool<'info>>,
     campaign_id: [u8; 32],
     eligibility_root: [u8; 32],
     zk_verifier_program: Pubkey,
+    options: VotingPoolOptions,
 ) -> Result<()> {
-    let pool = &mut ctx.accounts.voting_pool;
+    // Campaigns with on-chain terms get their dispute pool from `open_dispute`.
+    require!(
+        ctx.accounts.campaign.data_is_empty(),
+        ErrorCode::DisputeUnavailable
+    );
+
     let inco = ctx.accounts.inco_lightning_program.to_account_info();
     let signer = ctx.accounts.authority.to_account_info();
-    
+    setup_voting_pool(
+        &mut ctx.accounts.voting_pool,
+        &inco,
+        &signer,
+        signer.key(),
+        campaign_id,
+        eligibility_root,
+        zk_verifier_program,
+        &options,
+    )
+}
+
+/// Rejects option combinations a pool can't tally.
+pub(crate) fn validate_pool_options(options: &VotingPoolOptions) -> Result<()> {
+    require!(
+        !(options.encrypt_turnout && options.min_votes > 0),
+        ErrorCode::InvalidPoolOptions
+    );
+    match options.ballot_type {
+        BallotType::SingleChoice => {
+            require!(options.option_count == 0, ErrorCode::InvalidPoolOptions);
+        }
+        BallotType::Approval | BallotType::Quadratic => {
+            // The encrypted outcome and revote undo both assume two fixed tallies.
+            require!(
+                (2..=MAX_BALLOT_OPTIONS).contains(&(options.option_count as usize)),
+                ErrorCode::InvalidPoolOptions
+            );
+            require!(
+                options.reveal_mode == RevealMode::Tallies && !options.allow_revote,
+                ErrorCode::InvalidPoolOptions
+            );
+        }
+    }
+    // Quadratic budgets are the leaf weight proven by `weighted_vote_eligibility`.
+    require!(
+        options.ballot_type != BallotType::Quadratic || options.weighted,
+        ErrorCode::InvalidPoolOptions
+    );
+    let question_count = options.question_count.max(1);
+    if question_count > 1 {
+        // The circuit takes a fixed number of ciphertexts, so no other count can be proven.
+        require!(
+            question_count as usize == MAX_QUESTIONS,
+            ErrorCode::InvalidPoolOptions
+        );
+        // The multi-question circuit is unweighted, and the encrypted outcome
+        // and revote undo only cover the first question.
+        require!(
+            options.ballot_type == BallotType::SingleChoice
+                && options.reveal_mode == RevealMode::Tallies
+                && !options.allow_revote
+                && !options.weighted,
+            ErrorCode::InvalidPoolOptions
+        );
+    }
+    let policy = &options.outcome_policy;
+    require!(
+        policy.tie_break != VoteOutcome::Pending
+            && policy.quorum_default != VoteOutcome::Pending
+            && policy.quorum_bps as u64 <= QUORUM_BPS_DENOMINATOR,
+        ErrorCode::InvalidPoolOptions
+    );
+    // A quorum is a share of the eligible set, so the set's size must be known.
+    require!(
+        policy.quorum_bps == 0 || options.eligible_count > 0,
+        ErrorCode::InvalidPoolOptions
+    );
+    // The hook is called with the pool as a signer, which must never reach this program.
+    require!(
+        options.outcome_hook_program != crate::ID,
+        ErrorCode::InvalidPoolOptions
+    );
+    Ok(())
+}
+
+/// Validates `options` and writes a fresh pool with zeroed encrypted tallies,
+/// owned by `authority`. `signer` creates the Inco handles.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn setup_voting_pool<'info>(
+    pool: &mut Account<'info, VotingPool>,
+    inco: &AccountInfo<'info>,
+    signer: &AccountInfo<'info>,
+    authority: Pubkey,
+    campaign_id: [u8; 32],
+    eligibility_root: [u8; 32],
+    zk_verifier_program: Pubkey,
+    options: &VotingPoolOptions,
+) -> Result<()> {
+    validate_pool_options(options)?;
+    let question_count = options.question_count.max(1);
+
+    pool.version = VotingPool::VERSION;
     pool.campaign_id = campaign_id;
-    pool.authority = ctx.accounts.authority.key();
+    pool.authority = authority;
     pool.eligibility_root = eligibility_root;
     pool.zk_verifier_program = zk_verifier_program;
     pool.total_votes = 0;
     pool.is_active = true;
+    pool.encrypt_turnout = options.encrypt_turnout;
+    pool.has_votes = false;
+    pool.reveal_mode = options.reveal_mode;
+    pool.min_votes = options.min_votes;
+    pool.is_void = false;
+    pool.outcome = VoteOutcome::Pending;
+    pool.public_reveal = options.public_reveal;
+    pool.weighted = options.weighted;
+    pool.allow_revote = options.allow_revote;
+    pool.ballot_type = options.ballot_type;
+    pool.option_count = options.option_count;
+    pool.question_count = question_count;
+    pool.eligible_count = options.eligible_count;
+    pool.appeal_bond = options.appeal_bond;
+    pool.max_appeal_rounds = options.max_appeal_rounds;
+    pool.outcome_policy = options.outcome_policy;
+    pool.outcome_hook_program = options.outcome_hook_program;
+    pool.outcome_hook_discriminator = options.outcome_hook_discriminator;
+    pool.outcome_executed = false;
+    pool.closed_at = 0;
     
     let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
-    pool.refund_host_votes = as_euint128(cpi_ctx, 0)?;
+    pool.refund_host_votes = as_euint128(cpi_ctx, 0)?.into();
+
+    pool.option_tallies = Vec::with_capacity(options.option_count as usize);
+    for _ in 0..options.option_count {
+        let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+        pool.option_tallies.push(as_euint128(cpi_ctx, 0)?.into());
+    }
+
+    pool.question_tallies = Vec::with_capacity(2 * (question_count as usize - 1));
+    for _ in 1..question_count {
+        for _ in 0..2 {
+            let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+            pool.question_tallies.push(as_euint128(cpi_ctx, 0)?.into());
+        }
+    }
+
+    if pool.encrypt_turnout {
+        let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+        pool.encrypted_total_votes = as_euint128(cpi_ctx, 0)?.into();
+    }
     
-    let cpi_ctx = CpiContext::new(inco, Operation { signer });
-    pool.equal_distribution_votes = as_euint128(cpi_ctx, 0)?;
+    let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    pool.equal_distribution_votes = as_euint128(cpi_ctx, 0)?.into();
+
+    emit!(VotingPoolInitialized {
+        schema_version: EVENT_SCHEMA_VERSION,
+        campaign_id,
+        voting_pool: pool.key(),
+        authority: pool.authority,
+        eligibility_root,
+        zk_verifier_program,
+        encrypt_turnout: pool.encrypt_turnout,
+        reveal_mode: pool.reveal_mode,
+        min_votes: pool.min_votes,
+        public_reveal: pool.public_reveal,
+        weighted: pool.weighted,
+        allow_revote: pool.allow_revote,
+        ballot_type: pool.ballot_type,
+        option_count: pool.option_count,
+        question_count: pool.question_count,
+        eligible_count: pool.eligible_count,
+        appeal_bond: pool.appeal_bond,
+        max_appeal_rounds: pool.max_appeal_rounds,
+        outcome_policy: pool.outcome_policy,
+        outcome_hook_program: pool.outcome_hook_program,
+    });
     
     Ok(())
 }
 
-pub fn set_eligibility_root<'info>(
+pub(crate) fn set_eligibility_root<'info>(
     ctx: Context<'_, '_, '_, 'info, SetEligibilityRoot<'info>>,
-    _campaign_id: [u8; 32],
+    campaign_id: [u8; 32],
     eligibility_root: [u8; 32],
+    eligible_count: u64,
 ) -> Result<()> {
     let voting_pool = &mut ctx.accounts.voting_pool;
+    // Jurors are drawn as leaf indices into the tree set when the seed was committed.
+    require!(ctx.accounts.jury.data_is_empty(), ErrorCode::EligibilityFrozen);
     voting_pool.eligibility_root = eligibility_root;
+    voting_pool.eligible_count = eligible_count;
+
+    emit!(EligibilityRootUpdated {
+        schema_version: EVENT_SCHEMA_VERSION,
+        campaign_id,
+        voting_pool: voting_pool.key(),
+        eligibility_root,
+        eligible_count,
+    });
+
     Ok(())
 }
 
-pub fn cast_vote_zk<'info>(
+pub(crate) fn cast_vote_zk<'info>(
     ctx: Context<'_, '_, '_, 'info, CastVoteZk<'info>>,
     campaign_id: [u8; 32],
     nullifier_value: [u8; 32],
@@ -169,24 +769,203 @@ pub fn cast_vote_zk<'info>(
     encrypted_vote: Vec<u8>,
 ) -> Result<()> {
     let voting_pool = &mut ctx.accounts.voting_pool;
+    // A vote would rule out the committed draw and leave the pool stuck.
+    require!(!voting_pool.jury_pending, ErrorCode::JuryDrawPending);
+    let weight = verify_ballot(
+        voting_pool,
+        &ctx.accounts.zk_verifier_program,
+        &nullifier_value,
+        &proof,
+        &public_witness,
+        &vote_commitment(voting_pool, &encrypted_vote, 0)?,
+    )?;
+
+    let inco = ctx.accounts.inco_lightning_program.to_account_info();
+    let signer = ctx.accounts.relayer.to_account_info();
+    let delegated_weight = ctx
+        .accounts
+        .delegation
+        .as_ref()
+        .map(|delegation| delegation.weight.into());
+    let contribution = match voting_pool.ballot_type {
+        BallotType::SingleChoice => Some(tally_ballot(
+            voting_pool,
+            &inco,
+            &signer,
+            encrypted_vote,
+            weight,
+            delegated_weight,
+        )?),
+        BallotType::Approval => {
+            tally_approval_ballot(
+                voting_pool,
+                &inco,
+                &signer,
+                encrypted_vote,
+                weight,
+                delegated_weight,
+            )?;
+            None
+        }
+        BallotType::Quadratic => {
+            tally_quadratic_ballot(
+                voting_pool,
+                &inco,
+                &signer,
+                encrypted_vote,
+                weight,
+                delegated_weight,
+            )?;
+            None
+        }
+    };
+
+    let nullifier_account = &mut ctx.accounts.nullifier;
+    nullifier_account.campaign_id = campaign_id;
+    nullifier_account.value = nullifier_value;
+    // Multi-option pools can't enable revote, so only single-choice ballots reach this.
+    if let Some(contribution) = contribution.filter(|_| voting_pool.allow_revote) {
+        nullifier_account.sequence = 0;
+        nullifier_account.refund_contribution = contribution.refund_host.into();
+        nullifier_account.equal_contribution = contribution.equal_distribution.into();
+    }
+
+    if voting_pool.encrypt_turnout {
+        let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+        let one = as_euint128(cpi_ctx, 1)?;
+        let cpi_ctx = CpiContext::new(inco, Operation { signer });
+        voting_pool.encrypted_total_votes =
+            e_add(cpi_ctx, voting_pool.encrypted_total_votes.into(), one, 0)?.into();
+    } else {
+        voting_pool.total_votes += 1;
+        voting_pool.cast_weight = voting_pool
+            .cast_weight
+            .checked_add(weight)
+            .ok_or(ErrorCode::InvalidVoteWeight)?;
+    }
+    voting_pool.has_votes = true;
+
+    emit!(VoteCast {
+        schema_version: EVENT_SCHEMA_VERSION,
+        campaign_id,
+        voting_pool: voting_pool.key(),
+        nullifier: nullifier_value,
+        weight,
+        delegated: delegated_weight.is_some(),
+        total_votes: voting_pool.public_total_votes(),
+    });
+
+    Ok(())
+}
+
+pub(crate) fn recast_vote_zk<'info>(
+    ctx: Context<'_, '_, '_, 'info, RecastVoteZk<'info>>,
+    campaign_id: [u8; 32],
+    nullifier_value: [u8; 32],
+    sequence: u64,
+    proof: Vec<u8>,
+    public_witness: Vec<u8>,
+    encrypted_vote: Vec<u8>,
+) -> Result<()> {
+    let voting_pool = &mut ctx.accounts.voting_pool;
+    require!(voting_pool.allow_revote, ErrorCode::RevoteDisabled);
+
+    let nullifier_account = &mut ctx.accounts.nullifier;
+    require!(
+        sequence > nullifier_account.sequence,
+        ErrorCode::StaleBallotSequence
+    );
+
+    let weight = verify_ballot(
+        voting_pool,
+        &ctx.accounts.zk_verifier_program,
+        &nullifier_value,
+        &proof,
+        &public_witness,
+        &vote_commitment(voting_pool, &encrypted_vote, sequence)?,
+    )?;
+
+    let inco = ctx.accounts.inco_lightning_program.to_account_info();
+    let signer = ctx.accounts.relayer.to_account_info();
+
+    // Withdraw the previous ballot before counting the replacement.
+    let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    voting_pool.refund_host_votes = e_sub(
+        cpi_ctx,
+        voting_pool.refund_host_votes.into(),
+        nullifier_account.refund_contribution.into(),
+        0,
+    )?
+    .into();
+
+    let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    voting_pool.equal_distribution_votes = e_sub(
+        cpi_ctx,
+        voting_pool.equal_distribution_votes.into(),
+        nullifier_account.equal_contribution.into(),
+        0,
+    )?
+    .into();
+
+    // Revote pools refuse delegations, so there is no delegated weight to carry over.
+    let contribution = tally_ballot(voting_pool, &inco, &signer, encrypted_vote, weight, None)?;
+    nullifier_account.sequence = sequence;
+    nullifier_account.refund_contribution = contribution.refund_host.into();
+    nullifier_account.equal_contribution = contribution.equal_distribution.into();
+
+    emit!(VoteRecast {
+        schema_version: EVENT_SCHEMA_VERSION,
+        campaign_id,
+        voting_pool: voting_pool.key(),
+        nullifier: nullifier_value,
+        sequence,
+        weight,
+    });
+
+    Ok(())
+}
+
+/// Encrypted amounts one ballot added to each tally.
+pub(crate) struct BallotContribution {
+    pub refund_host: Euint128,
+    pub equal_distribution: Euint128,
+}
+
+/// Checks a ballot against the pool and runs the ZK verifier, returning its weight.
+///
+/// `commitment` comes from `vote_commitment` or `delegation_commitment`.
+pub(crate) fn verify_ballot(
+    voting_pool: &VotingPool,
+    zk_verifier_program: &AccountInfo,
+    nullifier_value: &[u8; 32],
+    proof: &[u8],
+    public_witness: &[u8],
+    commitment: &[u8; 32],
+) -> Result<u64> {
     require!(voting_pool.is_active, ErrorCode::VotingNotActive);
     require!(
-        ctx.accounts.zk_verifier_program.key() == voting_pool.zk_verifier_program,
+        voting_pool.voting_ends_at == 0
+            || Clock::get()?.unix_timestamp <= voting_pool.voting_ends_at,
+        ErrorCode::VotingWindowClosed
+    );
+    require!(
+        zk_verifier_program.key() == voting_pool.ballot_verifier(),
         ErrorCode::InvalidZkVerifier
     );
     require!(proof.len() == ZK_PROOF_LEN, ErrorCode::InvalidProofLength);
     require!(
-        public_witness.len() == ZK_PUBLIC_WITNESS_LEN,
+        public_witness.len() == voting_pool.public_witness_len(),
         ErrorCode::InvalidPublicWitnessLength
     );
-    require!(
-        encrypted_vote.len() == CIPHERTEXT_LEN,
-        ErrorCode::InvalidCiphertextLength
-    );
 
-    let witness_root = &public_witness[12..44];
-    let witness_nullifier = &public_witness[44..76];
-    let witness_commitment = &public_witness[76..108];
+    let witness_root = witness_field(public_witness, 0);
+    let witness_nullifier = witness_field(public_witness, 1);
+    let witness_commitment = witness_field(public_witness, 2);
+    let weight = if voting_pool.weighted {
+        parse_weight(witness_field(public_witness, 3))?
+    } else {
+        1
+    };
 
     require!(
         witness_root == voting_pool.eligibility_root.as_ref(),
@@ -196,33 +975,146 @@ pub fn cast_vote_zk<'info>(
         witness_nullifier == nullifier_value.as_ref(),
         ErrorCode::NullifierMismatch
     );
+    // Jury pools are never weighted, so the jury root takes the weight's slot.
+    if voting_pool.has_jury() {
+        require!(
+            witness_field(public_witness, 3) == voting_pool.jury_root.as_ref(),
+            ErrorCode::MerkleRootMismatch
+        );
+    }
 
-    let commitment_bytes = poseidon_hash_bytes(&encrypted_vote)?;
     require!(
-        commitment_bytes.as_ref() == witness_commitment,
+        commitment.as_ref() == witness_commitment,
         ErrorCode::CommitmentMismatch
     );
 
+    invoke_verifier(zk_verifier_program, proof, public_witness)?;
+
+    Ok(weight)
+}
+
+/// Has the verifier program check `proof` against `public_witness`; it fails
+/// the instruction on a bad proof.
+pub(crate) fn invoke_verifier(
+    zk_verifier_program: &AccountInfo,
+    proof: &[u8],
+    public_witness: &[u8],
+) -> Result<()> {
     let mut verifier_data = Vec::with_capacity(proof.len() + public_witness.len());
-    verifier_data.extend_from_slice(&proof);
-    verifier_data.extend_from_slice(&public_witness);
+    verifier_data.extend_from_slice(proof);
+    verifier_data.extend_from_slice(public_witness);
 
     let verify_ix = Instruction {
-        program_id: ctx.accounts.zk_verifier_program.key(),
+        program_id: zk_verifier_program.key(),
         accounts: vec![],
         data: verifier_data,
     };
     invoke(&verify_ix, &[])?;
+    Ok(())
+}
 
-    let nullifier_account = &mut ctx.accounts.nullifier;
-    nullifier_account.campaign_id = campaign_id;
-    nullifier_account.value = nullifier_value;
+/// Adds `weight`, plus any delegated weight, to the tally the encrypted choice selects.
+///
+/// Multi-question ballots carry one ciphertext per question; the first goes to
+/// the refund/equal tallies and is the contribution returned.
+pub(crate) fn tally_ballot<'info>(
+    voting_pool: &mut VotingPool,
+    inco: &AccountInfo<'info>,
+    signer: &AccountInfo<'info>,
+    encrypted_vote: Vec<u8>,
+    weight: u64,
+    delegated_weight: Option<Euint128>,
+) -> Result<BallotContribution> {
+    let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let one = as_euint128(cpi_ctx, 1)?;
 
-    let inco = ctx.accounts.inco_lightning_program.to_account_info();
-    let signer = ctx.accounts.relayer.to_account_info();
+    let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let zero = as_euint128(cpi_ctx, 0)?;
+
+    let vote_weight = ballot_weight(inco, signer, one, weight, delegated_weight)?;
+
+    let mut answers = encrypted_vote.chunks(CIPHERTEXT_LEN);
+    let first = answers.next().ok_or(ErrorCode::InvalidCiphertextLength)?;
+    let (add_to_refund, add_to_equal) = tally_choice(
+        inco,
+        signer,
+        first,
+        one,
+        zero,
+        vote_weight,
+        &mut voting_pool.refund_host_votes,
+        &mut voting_pool.equal_distribution_votes,
+    )?;
+
+    for (answer, tallies) in answers.zip(voting_pool.question_tallies.chunks_mut(2)) {
+        let (choice_zero, choice_one) = tallies.split_at_mut(1);
+        tally_choice(
+            inco,
+            signer,
+            answer,
+            one,
+            zero,
+            vote_weight,
+            &mut choice_zero[0],
+            &mut choice_one[0],
+        )?;
+    }
+
+    Ok(BallotContribution {
+        refund_host: add_to_refund,
+        equal_distribution: add_to_equal,
+    })
+}
+
+/// Adds `vote_weight` to `choice_one` if the encrypted choice is 1, otherwise
+/// to `choice_zero`, returning the amounts added to each.
+#[allow(clippy::too_many_arguments)]
+fn tally_choice<'info>(
+    inco: &AccountInfo<'info>,
+    signer: &AccountInfo<'info>,
+    encrypted_choice: &[u8],
+    one: Euint128,
+    zero: Euint128,
+    vote_weight: Euint128,
+    choice_zero: &mut IncoHandle,
+    choice_one: &mut IncoHandle,
+) -> Result<(Euint128, Euint128)> {
+    let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let encrypted_choice = new_euint128(cpi_ctx, encrypted_choice.to_vec(), 0)?;
+
+    let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let is_one = e_eq(cpi_ctx, encrypted_choice, one, 0)?;
+
+    let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let add_to_one = e_select(cpi_ctx, is_one, vote_weight, zero, 0)?;
+
+    let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    *choice_one = e_add(cpi_ctx, (*choice_one).into(), add_to_one, 0)?.into();
+
+    let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let add_to_zero = e_select(cpi_ctx, is_one, zero, vote_weight, 0)?;
 
     let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
-    let encrypted_choice = new_euint128(cpi_ctx, encrypted_vote, 0)?;
+    *choice_zero = e_add(cpi_ctx, (*choice_zero).into(), add_to_zero, 0)?.into();
+
+    Ok((add_to_zero, add_to_one))
+}
+
+/// Adds `weight`, plus any delegated weight, to each option the encrypted bitmask approves.
+///
+/// The eligibility circuit range-checks the mask the voter says they encrypted,
+/// but can't open the Inco ciphertext, so the width is enforced here too: a
+/// mask with bits at or above `option_count` is counted as an empty ballot.
+pub(crate) fn tally_approval_ballot<'info>(
+    voting_pool: &mut VotingPool,
+    inco: &AccountInfo<'info>,
+    signer: &AccountInfo<'info>,
+    encrypted_vote: Vec<u8>,
+    weight: u64,
+    delegated_weight: Option<Euint128>,
+) -> Result<()> {
+    let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let encrypted_mask = new_euint128(cpi_ctx, encrypted_vote, 0)?;
 
     let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
     let one = as_euint128(cpi_ctx, 1)?;
@@ -231,27 +1123,303 @@ pub fn cast_vote_zk<'info>(
     let zero = as_euint128(cpi_ctx, 0)?;
 
     let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
-    let is_equal_dist = e_eq(cpi_ctx, encrypted_choice, one, 0)?;
+    let width_limit = as_euint128(cpi_ctx, 1u128 << voting_pool.option_count)?;
 
     let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
-    let add_to_equal = e_select(cpi_ctx, is_equal_dist, one, zero, 0)?;
+    let within_width = e_lt(cpi_ctx, encrypted_mask, width_limit, 0)?;
 
     let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
-    voting_pool.equal_distribution_votes = e_add(cpi_ctx, voting_pool.equal_distribution_votes, add_to_equal, 0)?;
+    let mask = e_select(cpi_ctx, within_width, encrypted_mask, zero, 0)?;
+
+    let unit_weight = weight == 1 && delegated_weight.is_none();
+    let vote_weight = ballot_weight(inco, signer, one, weight, delegated_weight)?;
+
+    for (index, tally) in voting_pool.option_tallies.iter_mut().enumerate() {
+        let shifted = if index == 0 {
+            mask
+        } else {
+            let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+            let shift = as_euint128(cpi_ctx, index as u128)?;
+            let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+            e_shr(cpi_ctx, mask, shift, 0)?
+        };
+
+        let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+        let approved = e_and(cpi_ctx, shifted, one, 0)?;
 
+        let added = if unit_weight {
+            approved
+        } else {
+            let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+            e_mul(cpi_ctx, approved, vote_weight, 0)?
+        };
+
+        let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+        *tally = e_add(cpi_ctx, (*tally).into(), added, 0)?.into();
+    }
+
+    Ok(())
+}
+
+/// Adds each option's votes from the packed allocation, provided their squares
+/// fit within the voice-credit budget.
+///
+/// The budget is the proven leaf weight plus any delegated weight. Over-budget
+/// ballots are zeroed with `e_select` rather than rejected, since the program
+/// never learns whether the check passed.
+pub(crate) fn tally_quadratic_ballot<'info>(
+    voting_pool: &mut VotingPool,
+    inco: &AccountInfo<'info>,
+    signer: &AccountInfo<'info>,
+    encrypted_vote: Vec<u8>,
+    weight: u64,
+    delegated_weight: Option<Euint128>,
+) -> Result<()> {
     let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
-    let add_to_refund = e_select(cpi_ctx, is_equal_dist, zero, one, 0)?;
+    let allocation = new_euint128(cpi_ctx, encrypted_vote, 0)?;
+
+    let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let one = as_euint128(cpi_ctx, 1)?;
+
+    let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let zero = as_euint128(cpi_ctx, 0)?;
+
+    let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let lane_mask = as_euint128(cpi_ctx, (1u128 << QUADRATIC_LANE_BITS) - 1)?;
+
+    let budget = ballot_weight(inco, signer, one, weight, delegated_weight)?;
+
+    let option_count = voting_pool.option_count as usize;
+    let mut votes = Vec::with_capacity(option_count);
+    let mut cost = zero;
+    for index in 0..option_count {
+        let shifted = if index == 0 {
+            allocation
+        } else {
+            let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+            let shift = as_euint128(cpi_ctx, index as u128 * QUADRATIC_LANE_BITS as u128)?;
+            let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+            e_shr(cpi_ctx, allocation, shift, 0)?
+        };
+
+        let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+        let option_votes = e_and(cpi_ctx, shifted, lane_mask, 0)?;
+
+        let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+        let squared = e_mul(cpi_ctx, option_votes, option_votes, 0)?;
+
+        let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+        cost = e_add(cpi_ctx, cost, squared, 0)?;
+        votes.push(option_votes);
+    }
+
+    let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let within_budget = e_le(cpi_ctx, cost, budget, 0)?;
+
+    for (tally, option_votes) in voting_pool.option_tallies.iter_mut().zip(votes) {
+        let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+        let counted = e_select(cpi_ctx, within_budget, option_votes, zero, 0)?;
+
+        let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+        *tally = e_add(cpi_ctx, (*tally).into(), counted, 0)?.into();
+    }
+
+    Ok(())
+}
+
+/// Encrypted weight a ballot counts for: its own weight plus any delegated to it.
+fn ballot_weight<'info>(
+    inco: &AccountInfo<'info>,
+    signer: &AccountInfo<'info>,
+    one: Euint128,
+    weight: u64,
+    delegated_weight: Option<Euint128>,
+) -> Result<Euint128> {
+    let own_weight = if weight == 1 {
+        one
+    } else {
+        let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+        as_euint128(cpi_ctx, weight as u128)?
+    };
+    match delegated_weight {
+        Some(delegated) => {
+            let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+            e_add(cpi_ctx, own_weight, delegated, 0)
+        }
+        None => Ok(own_weight),
+    }
+}
+
+/// Commitment a vote's proof must carry.
+///
+/// Multi-question pools commit to each answer as a first ballot, then hash
+/// those commitments together in question order.
+pub(crate) fn vote_commitment(
+    voting_pool: &VotingPool,
+    encrypted_vote: &[u8],
+    sequence: u64,
+) -> Result<[u8; 32]> {
+    if voting_pool.question_count <= 1 {
+        let approval_options = if voting_pool.ballot_type == BallotType::Approval {
+            voting_pool.option_count
+        } else {
+            0
+        };
+        return poseidon_hash_bytes(&vote_ballot(encrypted_vote, sequence, approval_options)?);
+    }
+
+    require!(
+        encrypted_vote.len() == voting_pool.question_count as usize * CIPHERTEXT_LEN,
+        ErrorCode::InvalidCiphertextLength
+    );
+    let mut question_commitments = Vec::with_capacity(voting_pool.question_count as usize);
+    for answer in encrypted_vote.chunks(CIPHERTEXT_LEN) {
+        question_commitments.push(poseidon_hash_bytes(&vote_ballot(answer, 0, 0)?)?);
+    }
+    let refs: Vec<&[u8]> = question_commitments.iter().map(|field| field.as_ref()).collect();
+    let hash = poseidon_hashv(Parameters::Bn254X5, Endianness::BigEndian, &refs)
+        .map_err(|_| ErrorCode::InvalidPoseidonInput)?;
+    Ok(hash.to_bytes())
+}
+
+/// Commitment preimage for a vote: the ciphertext, then metadata holding the
+/// sequence and an approval pool's option count. `sequence` is bound so a
+/// replacement ballot can't be replayed under a newer sequence number; first
+/// ballots use 0 and hash like the bare ciphertext. The circuit checks the
+/// voter's approval mask against `approval_options`, which is 0 otherwise.
+fn vote_ballot(encrypted_vote: &[u8], sequence: u64, approval_options: u8) -> Result<Vec<u8>> {
+    require!(
+        encrypted_vote.len() == CIPHERTEXT_LEN,
+        ErrorCode::InvalidCiphertextLength
+    );
+    let mut ballot = vec![0u8; BALLOT_LEN];
+    ballot[..CIPHERTEXT_LEN].copy_from_slice(encrypted_vote);
+    ballot[CIPHERTEXT_LEN..CIPHERTEXT_LEN + 8].copy_from_slice(&sequence.to_be_bytes());
+    ballot[CIPHERTEXT_LEN + 8] = approval_options;
+    Ok(ballot)
+}
+
+/// Commitment a delegation's proof must carry: the delegate's nullifier in
+/// place of a ciphertext, tagged in the last metadata byte so it can't pass as
+/// a vote.
+pub(crate) fn delegation_commitment(delegate_nullifier: &[u8; 32]) -> Result<[u8; 32]> {
+    tagged_commitment(delegate_nullifier, BALLOT_KIND_DELEGATION)
+}
 
-    let cpi_ctx = CpiContext::new(inco, Operation { signer });
-    voting_pool.refund_host_votes = e_add(cpi_ctx, voting_pool.refund_host_votes, add_to_refund, 0)?;
+/// Commitment a dispute opener's proof must carry: the campaign id in place of
+/// a ciphertext, tagged so the proof can't pass as a vote or a delegation. The
+/// tag also moves the proof's nullifier into the circuit's dispute domain.
+pub(crate) fn dispute_commitment(campaign_id: &[u8; 32]) -> Result<[u8; 32]> {
+    tagged_commitment(campaign_id, BALLOT_KIND_DISPUTE)
+}
+
+/// Commitment a recipient's evidence proof must carry, over a digest of the
+/// record's content hash and key ciphertext. The tag puts the proof's
+/// nullifier in the circuit's evidence domain.
+pub(crate) fn evidence_commitment(record_digest: &[u8; 32]) -> Result<[u8; 32]> {
+    tagged_commitment(record_digest, BALLOT_KIND_EVIDENCE)
+}
 
-    voting_pool.total_votes += 1;
+/// Commitment a voter's proof must carry to read evidence keys as `reader`.
+/// The tag puts the proof's nullifier in the circuit's evidence-access domain.
+pub(crate) fn evidence_access_commitment(reader: &Pubkey) -> Result<[u8; 32]> {
+    tagged_commitment(&reader.to_bytes(), BALLOT_KIND_EVIDENCE_ACCESS)
+}
+
+/// A 32-byte payload in place of a ciphertext, tagged with its kind in the last
+/// metadata byte so proofs for one purpose can't be replayed for another.
+fn tagged_commitment(payload: &[u8; 32], kind: u8) -> Result<[u8; 32]> {
+    let mut ballot = vec![0u8; BALLOT_LEN];
+    ballot[..32].copy_from_slice(payload);
+    ballot[BALLOT_LEN - 1] = kind;
+    poseidon_hash_bytes(&ballot)
+}
+
+pub(crate) fn migrate_voting_pool<'info>(
+    ctx: Context<'_, '_, '_, 'info, MigrateVotingPool<'info>>,
+    campaign_id: [u8; 32],
+) -> Result<()> {
+    let info = ctx.accounts.voting_pool.to_account_info();
+    let mut pool = read_stored_pool(&info)?;
+
+    require!(
+        pool.authority == ctx.accounts.authority.key(),
+        ErrorCode::Unauthorized
+    );
+    require!(pool.version < VotingPool::VERSION, ErrorCode::AlreadyMigrated);
+    let from_version = upgrade_pool(&mut pool);
+
+    migration::resize_account(
+        &info,
+        &ctx.accounts.authority.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        8 + VotingPool::INIT_SPACE,
+    )?;
+    migration::write_account(&info, &pool)?;
+
+    emit!(AccountMigrated {
+        schema_version: EVENT_SCHEMA_VERSION,
+        campaign_id,
+        account: info.key(),
+        from_version,
+        to_version: pool.version,
+    });
 
     Ok(())
 }
 
-fn poseidon_hash_bytes(bytes: &[u8]) -> Result<[u8; 32]> {
-    let chunks = (bytes.len() + POSEIDON_CHUNK_LEN - 1) / POSEIDON_CHUNK_LEN;
+/// Decodes a pool stored in the unversioned layout or the versioned one.
+fn read_stored_pool(info: &AccountInfo) -> Result<VotingPool> {
+    if info.data_len() != 8 + LegacyVotingPool::LEN {
+        return migration::read_padded::<VotingPool>(info, VotingPool::INIT_SPACE);
+    }
+    let data = info.try_borrow_data()?;
+    require!(
+        data[..8] == *VotingPool::DISCRIMINATOR,
+        anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch
+    );
+    Ok(LegacyVotingPool::deserialize(&mut &data[8..])?.into_current())
+}
+
+/// Fills in what unversioned pools didn't record and returns the version the
+/// pool was upgraded from.
+fn upgrade_pool(pool: &mut VotingPool) -> u8 {
+    let from_version = pool.version;
+    if from_version < 1 {
+        // Unversioned pools were single-question, unweighted and always
+        // counted turnout in plaintext.
+        pool.has_votes = pool.total_votes > 0;
+        pool.question_count = 1;
+        pool.cast_weight = pool.total_votes;
+        // `Default` decodes as `Pending`, which isn't a valid policy outcome.
+        pool.outcome_policy = OutcomePolicy::default();
+    }
+    pool.version = VotingPool::VERSION;
+    from_version
+}
+
+/// Length of a gnark public witness carrying `inputs` public inputs.
+pub(crate) fn public_witness_len(inputs: usize) -> usize {
+    ZK_WITNESS_HEADER_LEN + inputs * ZK_FIELD_LEN
+}
+
+/// Public input `index` of a gnark public witness, after its 12-byte header.
+pub(crate) fn witness_field(public_witness: &[u8], index: usize) -> &[u8] {
+    let start = ZK_WITNESS_HEADER_LEN + index * ZK_FIELD_LEN;
+    &public_witness[start..start + ZK_FIELD_LEN]
+}
+
+/// Weights are big-endian field elements; the circuit bounds them to 64 bits.
+fn parse_weight(field: &[u8]) -> Result<u64> {
+    let (high, low) = field.split_at(ZK_FIELD_LEN - 8);
+    require!(high.iter().all(|byte| *byte == 0), ErrorCode::InvalidVoteWeight);
+    let weight = u64::from_be_bytes(low.try_into().unwrap());
+    require!(weight > 0, ErrorCode::InvalidVoteWeight);
+    Ok(weight)
+}
+
+pub(crate) fn poseidon_hash_bytes(bytes: &[u8]) -> Result<[u8; 32]> {
+    let chunks = bytes.len().div_ceil(POSEIDON_CHUNK_LEN);
     let mut fields: Vec<[u8; 32]> = Vec::with_capacity(chunks);
 
     for i in 0..chunks {
@@ -275,14 +1443,22 @@ fn poseidon_hash_bytes(bytes: &[u8]) -> Result<[u8; 32]> {
     Ok(hash.to_bytes())
 }
 
-pub fn close_voting<'info>(
+pub(crate) fn close_voting<'info>(
     ctx: Context<'_, '_, '_, 'info, CloseVoting<'info>>,
-    _campaign_id: [u8; 32],
+    campaign_id: [u8; 32],
     allowed_address: Pubkey,
 ) -> Result<()> {
     let voting_pool = &mut ctx.accounts.voting_pool;
     require!(voting_pool.is_active, ErrorCode::VotingNotActive);
+    // Rounds with an on-chain window run it out unless the result is already settled.
+    require!(
+        voting_pool.voting_ends_at == 0
+            || voting_pool.decided_early
+            || Clock::get()?.unix_timestamp > voting_pool.voting_ends_at,
+        ErrorCode::VotingStillActive
+    );
     voting_pool.is_active = false;
+    voting_pool.closed_at = Clock::get()?.unix_timestamp;
     require!(
         allowed_address == ctx.accounts.allowed_address.key(),
         ErrorCode::InvalidAllowedAddress
@@ -290,29 +1466,584 @@ pub fn close_voting<'info>(
     
     let inco = ctx.accounts.inco_lightning_program.to_account_info();
     let signer = ctx.accounts.authority.to_account_info();
-    let allowed_key = ctx.accounts.allowed_address.key();
+    let system_program = ctx.accounts.system_program.to_account_info();
+
+    if voting_pool.total_votes < voting_pool.min_votes {
+        // Too few voters to reveal anything without exposing individual ballots.
+        voting_pool.is_void = true;
+        voting_pool.outcome = voting_pool.outcome_policy.quorum_default;
+
+        emit!(VotingVoided {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign_id,
+            voting_pool: voting_pool.key(),
+            total_votes: voting_pool.total_votes,
+            min_votes: voting_pool.min_votes,
+            outcome: voting_pool.outcome,
+        });
+
+        return Ok(());
+    }
+
+    // The outcome handle only exists after this call, so access is granted
+    // separately through `grant_outcome_access`.
+    if voting_pool.ballot_type == BallotType::SingleChoice {
+        voting_pool.encrypted_outcome = compute_encrypted_outcome(
+            &inco,
+            &signer,
+            voting_pool.refund_host_votes.into(),
+            voting_pool.equal_distribution_votes.into(),
+            &voting_pool.outcome_policy,
+            voting_pool.eligible_count,
+        )?
+        .into();
+    }
+
+    if voting_pool.reveal_mode == RevealMode::OutcomeOnly {
+
+        emit!(VotingClosed {
+            schema_version: EVENT_SCHEMA_VERSION,
+            campaign_id,
+            voting_pool: voting_pool.key(),
+            decryptors: vec![],
+            public_reveal: voting_pool.public_reveal,
+            total_votes: voting_pool.public_total_votes(),
+        });
+
+        return Ok(());
+    }
+
+    let mut remaining_accounts = ctx.remaining_accounts;
+    let mut allowances = match voting_pool.ballot_type {
+        BallotType::SingleChoice => {
+            let mut allowances = vec![
+                ctx.accounts
+                    .allowance_refund
+                    .clone()
+                    .ok_or(ErrorCode::MissingAllowanceAccount)?,
+                ctx.accounts
+                    .allowance_equal
+                    .clone()
+                    .ok_or(ErrorCode::MissingAllowanceAccount)?,
+            ];
+            // Later questions' allowances lead `remaining_accounts`, like option tallies.
+            let question_tallies = voting_pool.question_tallies.len();
+            require!(
+                remaining_accounts.len() >= question_tallies,
+                ErrorCode::MissingAllowanceAccount
+            );
+            let (question_allowances, rest) = remaining_accounts.split_at(question_tallies);
+            remaining_accounts = rest;
+            allowances.extend_from_slice(question_allowances);
+            allowances
+        }
+        BallotType::Approval | BallotType::Quadratic => {
+            // Option tallies don't map to named accounts, so their allowances
+            // lead `remaining_accounts`, ahead of any extra decryptors.
+            let option_count = voting_pool.option_count as usize;
+            require!(
+                remaining_accounts.len() >= option_count,
+                ErrorCode::MissingAllowanceAccount
+            );
+            let (option_allowances, rest) = remaining_accounts.split_at(option_count);
+            remaining_accounts = rest;
+            option_allowances.to_vec()
+        }
+    };
+    if voting_pool.encrypt_turnout {
+        allowances.push(
+            ctx.accounts
+                .allowance_turnout
+                .clone()
+                .ok_or(ErrorCode::MissingAllowanceAccount)?,
+        );
+    }
+
+    let handles = voting_pool.revealed_handles();
+    allow_handles(
+        &inco,
+        &signer,
+        &system_program,
+        &ctx.accounts.allowed_address.to_account_info(),
+        &allowances,
+        &handles,
+    )?;
+    let mut decryptors = vec![allowed_address];
+    decryptors.extend(allow_remaining_decryptors(
+        &inco,
+        &signer,
+        &system_program,
+        remaining_accounts,
+        &handles,
+    )?);
+
+    emit!(VotingClosed {
+        schema_version: EVENT_SCHEMA_VERSION,
+        campaign_id,
+        voting_pool: voting_pool.key(),
+        decryptors,
+        public_reveal: voting_pool.public_reveal,
+        total_votes: voting_pool.public_total_votes(),
+    });
     
-    let cpi_ctx = CpiContext::new(
-        inco.clone(),
-        Allow {
-            allowance_account: ctx.accounts.allowance_refund.to_account_info(),
-            signer: signer.clone(),
-            allowed_address: ctx.accounts.allowed_address.to_account_info(),
-            system_program: ctx.accounts.system_program.to_account_info(),
-        },
+    Ok(())
+}
+
+pub(crate) fn grant_outcome_access<'info>(
+    ctx: Context<'_, '_, '_, 'info, GrantOutcomeAccess<'info>>,
+    campaign_id: [u8; 32],
+    allowed_address: Pubkey,
+) -> Result<()> {
+    let voting_pool = &ctx.accounts.voting_pool;
+    require!(!voting_pool.is_active, ErrorCode::VotingStillActive);
+    require!(!voting_pool.is_void, ErrorCode::VotingVoid);
+    require!(
+        voting_pool.encrypted_outcome.0 != 0,
+        ErrorCode::OutcomeUnavailable
     );
-    allow(cpi_ctx, voting_pool.refund_host_votes.0, true, allowed_key)?;
-    
-    let cpi_ctx = CpiContext::new(
-        inco,
-        Allow {
-            allowance_account: ctx.accounts.allowance_equal.to_account_info(),
-            signer,
-            allowed_address: ctx.accounts.allowed_address.to_account_info(),
-            system_program: ctx.accounts.system_program.to_account_info(),
-        },
+    require!(
+        allowed_address == ctx.accounts.allowed_address.key(),
+        ErrorCode::InvalidAllowedAddress
     );
-    allow(cpi_ctx, voting_pool.equal_distribution_votes.0, true, allowed_key)?;
-    
+
+    let inco = ctx.accounts.inco_lightning_program.to_account_info();
+    let signer = ctx.accounts.authority.to_account_info();
+    let system_program = ctx.accounts.system_program.to_account_info();
+    let handles = vec![voting_pool.encrypted_outcome.0];
+
+    allow_handles(
+        &inco,
+        &signer,
+        &system_program,
+        &ctx.accounts.allowed_address.to_account_info(),
+        &[ctx.accounts.allowance_outcome.to_account_info()],
+        &handles,
+    )?;
+    let mut decryptors = vec![allowed_address];
+    decryptors.extend(allow_remaining_decryptors(
+        &inco,
+        &signer,
+        &system_program,
+        ctx.remaining_accounts,
+        &handles,
+    )?);
+
+    emit!(OutcomeAccessGranted {
+        schema_version: EVENT_SCHEMA_VERSION,
+        campaign_id,
+        voting_pool: voting_pool.key(),
+        decryptors,
+    });
+
     Ok(())
 }
+
+/// Records the policy outcome computed at close, once its handle is attested
+/// to decrypt to `outcome_code`. Anyone holding the attestation may submit it.
+pub(crate) fn finalize_outcome<'info>(
+    ctx: Context<'_, '_, '_, 'info, FinalizeOutcome<'info>>,
+    campaign_id: [u8; 32],
+    outcome_code: u128,
+) -> Result<()> {
+    let voting_pool = &mut ctx.accounts.voting_pool;
+    require!(!voting_pool.is_active, ErrorCode::VotingStillActive);
+    require!(
+        voting_pool.outcome == VoteOutcome::Pending && voting_pool.encrypted_outcome.0 != 0,
+        ErrorCode::OutcomeUnavailable
+    );
+
+    attestation::verify_decryption(
+        &ctx.accounts.inco_lightning_program.to_account_info(),
+        &ctx.accounts.instructions.to_account_info(),
+        &ctx.accounts.requester.to_account_info(),
+        voting_pool.encrypted_outcome,
+        outcome_code,
+    )?;
+    voting_pool.outcome = match outcome_code {
+        OUTCOME_REFUND_HOST => VoteOutcome::RefundHost,
+        OUTCOME_EQUAL_DISTRIBUTION => VoteOutcome::EqualDistribution,
+        OUTCOME_TIE => VoteOutcome::Tie,
+        _ => return err!(ErrorCode::InvalidDecryptionAttestation),
+    };
+
+    emit!(OutcomeFinalized {
+        schema_version: EVENT_SCHEMA_VERSION,
+        campaign_id,
+        voting_pool: voting_pool.key(),
+        outcome: voting_pool.outcome,
+    });
+
+    Ok(())
+}
+
+pub(crate) fn grant_public_access<'info>(
+    ctx: Context<'_, '_, '_, 'info, GrantPublicAccess<'info>>,
+    campaign_id: [u8; 32],
+) -> Result<()> {
+    let voting_pool = &ctx.accounts.voting_pool;
+    require!(voting_pool.public_reveal, ErrorCode::PublicRevealDisabled);
+    require!(!voting_pool.is_active, ErrorCode::VotingStillActive);
+    require!(!voting_pool.is_void, ErrorCode::VotingVoid);
+
+    let requester = ctx.accounts.requester.to_account_info();
+    allow_handles(
+        &ctx.accounts.inco_lightning_program.to_account_info(),
+        &requester,
+        &ctx.accounts.system_program.to_account_info(),
+        &requester,
+        ctx.remaining_accounts,
+        &voting_pool.revealed_handles(),
+    )?;
+
+    emit!(PublicAccessGranted {
+        schema_version: EVENT_SCHEMA_VERSION,
+        campaign_id,
+        voting_pool: voting_pool.key(),
+        requester: requester.key(),
+    });
+
+    Ok(())
+}
+
+/// Grants `decryptor` access to each handle, paired in order with `allowances`.
+pub(crate) fn allow_handles<'info>(
+    inco: &AccountInfo<'info>,
+    signer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    decryptor: &AccountInfo<'info>,
+    allowances: &[AccountInfo<'info>],
+    handles: &[u128],
+) -> Result<()> {
+    require!(
+        allowances.len() == handles.len(),
+        ErrorCode::MissingAllowanceAccount
+    );
+
+    for (allowance, handle) in allowances.iter().zip(handles) {
+        let cpi_ctx = CpiContext::new(
+            inco.clone(),
+            Allow {
+                allowance_account: allowance.clone(),
+                signer: signer.clone(),
+                allowed_address: decryptor.clone(),
+                system_program: system_program.clone(),
+            },
+        );
+        allow(cpi_ctx, *handle, true, decryptor.key())?;
+    }
+
+    Ok(())
+}
+
+/// Extra decryptors arrive as `remaining_accounts` groups of
+/// `[decryptor, allowance for each revealed handle...]`.
+fn allow_remaining_decryptors<'info>(
+    inco: &AccountInfo<'info>,
+    signer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    remaining_accounts: &[AccountInfo<'info>],
+    handles: &[u128],
+) -> Result<Vec<Pubkey>> {
+    let stride = 1 + handles.len();
+    require!(
+        remaining_accounts.len().is_multiple_of(stride),
+        ErrorCode::InvalidDecryptorAccounts
+    );
+
+    let mut decryptors = Vec::with_capacity(remaining_accounts.len() / stride);
+    for group in remaining_accounts.chunks(stride) {
+        allow_handles(inco, signer, system_program, &group[0], &group[1..], handles)?;
+        decryptors.push(group[0].key());
+    }
+    Ok(decryptors)
+}
+
+/// Encrypts the outcome `policy` gives the tallies, as one of the `OUTCOME_*`
+/// values, without exposing the margin or turnout.
+fn compute_encrypted_outcome<'info>(
+    inco: &AccountInfo<'info>,
+    signer: &AccountInfo<'info>,
+    refund_host_votes: Euint128,
+    equal_distribution_votes: Euint128,
+    policy: &OutcomePolicy,
+    eligible_count: u64,
+) -> Result<Euint128> {
+    let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let margin = as_euint128(cpi_ctx, policy.min_margin.max(1) as u128)?;
+
+    let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let refund_ceiling = e_add(cpi_ctx, refund_host_votes, margin, 0)?;
+    let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let equal_wins = e_ge(cpi_ctx, equal_distribution_votes, refund_ceiling, 0)?;
+
+    let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let equal_ceiling = e_add(cpi_ctx, equal_distribution_votes, margin, 0)?;
+    let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let refund_wins = e_ge(cpi_ctx, refund_host_votes, equal_ceiling, 0)?;
+
+    let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let refund_host = as_euint128(cpi_ctx, OUTCOME_REFUND_HOST)?;
+
+    let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let equal_distribution = as_euint128(cpi_ctx, OUTCOME_EQUAL_DISTRIBUTION)?;
+
+    let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let tie_break = as_euint128(cpi_ctx, outcome_code(policy.tie_break))?;
+
+    let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let not_equal_wins = e_select(cpi_ctx, refund_wins, refund_host, tie_break, 0)?;
+
+    let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let decided = e_select(cpi_ctx, equal_wins, equal_distribution, not_equal_wins, 0)?;
+    if policy.quorum_bps == 0 {
+        return Ok(decided);
+    }
+
+    // Both tallies together are the weight cast on the first question.
+    let quorum = (eligible_count as u128 * policy.quorum_bps as u128)
+        .div_ceil(QUORUM_BPS_DENOMINATOR as u128);
+    let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let cast = e_add(cpi_ctx, refund_host_votes, equal_distribution_votes, 0)?;
+    let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let quorum = as_euint128(cpi_ctx, quorum)?;
+    let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let quorum_met = e_ge(cpi_ctx, cast, quorum, 0)?;
+
+    let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    let quorum_default = as_euint128(cpi_ctx, outcome_code(policy.quorum_default))?;
+    let cpi_ctx = CpiContext::new(inco.clone(), Operation { signer: signer.clone() });
+    e_select(cpi_ctx, quorum_met, decided, quorum_default, 0)
+}
+
+/// The `OUTCOME_*` value encoding `outcome`.
+fn outcome_code(outcome: VoteOutcome) -> u128 {
+    match outcome {
+        VoteOutcome::EqualDistribution => OUTCOME_EQUAL_DISTRIBUTION,
+        VoteOutcome::Tie => OUTCOME_TIE,
+        VoteOutcome::RefundHost | VoteOutcome::Pending => OUTCOME_REFUND_HOST,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Runs `f` on an account holding `data`, as `migrate_voting_pool` sees it.
+    fn with_account<T>(data: &mut [u8], f: impl FnOnce(&AccountInfo) -> T) -> T {
+        let key = Pubkey::new_unique();
+        let mut lamports = 0;
+        let info = AccountInfo::new(&key, false, true, &mut lamports, data, &crate::ID, false, 0);
+        f(&info)
+    }
+
+    fn legacy_pool_data(authority: &Pubkey, total_votes: u64) -> Vec<u8> {
+        let mut data = VotingPool::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(&[1u8; 32]);
+        data.extend_from_slice(authority.as_ref());
+        data.extend_from_slice(&[2u8; 32]);
+        data.extend_from_slice(&[3u8; 32]);
+        data.extend_from_slice(&11u128.to_le_bytes());
+        data.extend_from_slice(&12u128.to_le_bytes());
+        data.extend_from_slice(&total_votes.to_le_bytes());
+        data.push(1);
+        data
+    }
+
+    #[test]
+    fn migrates_unversioned_pools() {
+        let authority = Pubkey::new_unique();
+        let mut data = legacy_pool_data(&authority, 4);
+        assert_eq!(data.len(), 8 + LegacyVotingPool::LEN);
+
+        let mut pool = with_account(&mut data, read_stored_pool).unwrap();
+        assert_eq!(upgrade_pool(&mut pool), 0);
+        assert_eq!(pool.version, VotingPool::VERSION);
+        assert_eq!(pool.campaign_id, [1u8; 32]);
+        assert_eq!(pool.authority, authority);
+        assert_eq!(pool.refund_host_votes, IncoHandle(11));
+        assert_eq!(pool.equal_distribution_votes, IncoHandle(12));
+        assert!(pool.is_active && pool.has_votes);
+        assert_eq!(pool.question_count, 1);
+        assert_eq!(pool.cast_weight, 4);
+        assert_eq!(pool.outcome_policy, OutcomePolicy::default());
+        assert_eq!(pool.closed_at, 0);
+        // Unversioned pools allow no appeals, and a zero `closed_at` puts any
+        // window long past.
+        assert_eq!(pool.max_appeal_rounds, 0);
+        assert!(!pool.appeal_open(APPEAL_WINDOW_SECONDS + 1));
+    }
+
+    #[test]
+    fn rejects_unversioned_pools_of_another_account_type() {
+        let mut data = legacy_pool_data(&Pubkey::new_unique(), 0);
+        data[..8].copy_from_slice(&[0u8; 8]);
+        assert!(with_account(&mut data, read_stored_pool).is_err());
+    }
+
+    #[test]
+    fn appeal_window_runs_from_close_regardless_of_execution() {
+        let pool = VotingPool {
+            max_appeal_rounds: 1,
+            outcome_executed: true,
+            closed_at: 100,
+            ..Default::default()
+        };
+        assert!(pool.appeal_open(100 + APPEAL_WINDOW_SECONDS));
+        assert!(!pool.appeal_open(101 + APPEAL_WINDOW_SECONDS));
+
+        let last_round = VotingPool {
+            round: 1,
+            ..pool
+        };
+        assert!(!last_round.appeal_open(100));
+    }
+
+    #[test]
+    fn reads_versioned_pools_shorter_than_the_layout_by_zero_padding() {
+        // Fields appended after a pool was written read back as their defaults.
+        let pool = VotingPool {
+            version: VotingPool::VERSION,
+            authority: Pubkey::new_unique(),
+            total_votes: 3,
+            has_votes: true,
+            question_count: 2,
+            cast_weight: 3,
+            outcome_executed: true,
+            closed_at: 42,
+            jury_verifier_program: Pubkey::new_unique(),
+            jury_pending: true,
+            ..Default::default()
+        };
+        let mut data = Vec::new();
+        pool.try_serialize(&mut data).unwrap();
+        data.truncate(data.len() - (1 + 8 + 32 + 1));
+
+        let read = with_account(&mut data, read_stored_pool).unwrap();
+        assert_eq!(read.version, VotingPool::VERSION);
+        assert_eq!(read.authority, pool.authority);
+        assert_eq!(read.question_count, 2);
+        assert_eq!(read.cast_weight, 3);
+        assert!(!read.outcome_executed);
+        assert_eq!(read.closed_at, 0);
+        assert_eq!(read.jury_verifier_program, Pubkey::default());
+        assert!(!read.jury_pending);
+    }
+
+    #[test]
+    fn current_pools_read_back_unchanged() {
+        let pool = VotingPool {
+            version: VotingPool::VERSION,
+            closed_at: 42,
+            ..Default::default()
+        };
+        let mut data = Vec::new();
+        pool.try_serialize(&mut data).unwrap();
+        let read = with_account(&mut data, read_stored_pool).unwrap();
+        assert_eq!(read.version, VotingPool::VERSION);
+        assert_eq!(read.closed_at, 42);
+    }
+
+    #[test]
+    fn multi_question_pools_match_the_circuit() {
+        let options = |question_count| VotingPoolOptions {
+            question_count,
+            ..Default::default()
+        };
+        assert!(validate_pool_options(&options(1)).is_ok());
+        assert!(validate_pool_options(&options(MAX_QUESTIONS as u8)).is_ok());
+        assert!(validate_pool_options(&options(MAX_QUESTIONS as u8 + 1)).is_err());
+    }
+
+    fn weight_field(weight: u64) -> [u8; ZK_FIELD_LEN] {
+        let mut field = [0u8; ZK_FIELD_LEN];
+        field[ZK_FIELD_LEN - 8..].copy_from_slice(&weight.to_be_bytes());
+        field
+    }
+
+    #[test]
+    fn first_ballot_commits_like_the_bare_ciphertext() {
+        let ciphertext = [7u8; CIPHERTEXT_LEN];
+        assert_eq!(
+            poseidon_hash_bytes(&vote_ballot(&ciphertext, 0, 0).unwrap()).unwrap(),
+            poseidon_hash_bytes(&ciphertext).unwrap()
+        );
+    }
+
+    #[test]
+    fn vote_ballot_binds_the_sequence() {
+        let ciphertext = [7u8; CIPHERTEXT_LEN];
+        let first = vote_ballot(&ciphertext, 0, 0).unwrap();
+        let recast = vote_ballot(&ciphertext, 1, 0).unwrap();
+        assert_eq!(recast.len(), BALLOT_LEN);
+        assert_eq!(&recast[CIPHERTEXT_LEN..CIPHERTEXT_LEN + 8], &1u64.to_be_bytes());
+        assert_ne!(
+            poseidon_hash_bytes(&first).unwrap(),
+            poseidon_hash_bytes(&recast).unwrap()
+        );
+    }
+
+    #[test]
+    fn approval_ballots_commit_to_their_option_count() {
+        let ciphertext = [7u8; CIPHERTEXT_LEN];
+        let pool = |ballot_type, option_count| VotingPool {
+            ballot_type,
+            option_count,
+            ..Default::default()
+        };
+        let single = vote_commitment(&pool(BallotType::SingleChoice, 0), &ciphertext, 0).unwrap();
+        let approval = vote_commitment(&pool(BallotType::Approval, 3), &ciphertext, 0).unwrap();
+        let quadratic = vote_commitment(&pool(BallotType::Quadratic, 3), &ciphertext, 0).unwrap();
+        assert_eq!(single, quadratic);
+        assert_eq!(
+            approval,
+            poseidon_hash_bytes(&vote_ballot(&ciphertext, 0, 3).unwrap()).unwrap()
+        );
+        assert_ne!(approval, single);
+    }
+
+    #[test]
+    fn vote_ballot_rejects_other_lengths() {
+        assert_eq!(
+            vote_ballot(&[0u8; CIPHERTEXT_LEN - 1], 0, 0).unwrap_err(),
+            ErrorCode::InvalidCiphertextLength.into()
+        );
+    }
+
+    #[test]
+    fn tagged_commitments_differ_from_votes() {
+        let payload = [9u8; 32];
+        let mut ciphertext = [0u8; CIPHERTEXT_LEN];
+        ciphertext[..32].copy_from_slice(&payload);
+        let vote = poseidon_hash_bytes(&vote_ballot(&ciphertext, 0, 0).unwrap()).unwrap();
+        assert_ne!(vote, delegation_commitment(&payload).unwrap());
+        assert_ne!(vote, dispute_commitment(&payload).unwrap());
+        assert_ne!(
+            delegation_commitment(&payload).unwrap(),
+            dispute_commitment(&payload).unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_weight_reads_low_64_bits() {
+        assert_eq!(parse_weight(&weight_field(1)).unwrap(), 1);
+        assert_eq!(parse_weight(&weight_field(u64::MAX)).unwrap(), u64::MAX);
+    }
+
+    #[test]
+    fn parse_weight_rejects_zero() {
+        assert_eq!(
+            parse_weight(&weight_field(0)).unwrap_err(),
+            ErrorCode::InvalidVoteWeight.into()
+        );
+    }
+
+    #[test]
+    fn parse_weight_rejects_weights_above_64_bits() {
+        let mut field = weight_field(5);
+        field[ZK_FIELD_LEN - 9] = 1;
+        assert_eq!(
+            parse_weight(&field).unwrap_err(),
+            ErrorCode::InvalidVoteWeight.into()
+        );
+    }
+}
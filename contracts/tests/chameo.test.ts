@@ -22,11 +22,23 @@ const INCO_LIGHTNING_ID = new PublicKey("5sjEbPiqgZrYwR31ahR6Uk9wf5awoX61YGg7jEx
 const ZK_VERIFIER_PROGRAM_ID = new PublicKey(
   process.env.ZK_VERIFIER_PROGRAM_ID || "7n63xmE82LCYQkshU1QErzygTRuRXPnoG3U2AozKzT68"
 );
+// Pinned into the program at build time; the default key means claims are unavailable.
+const CLAIM_VERIFIER_PROGRAM_ID = new PublicKey(
+  process.env.CLAIM_VERIFIER_PROGRAM_ID || PublicKey.default.toBase58()
+);
+const TOKEN_PROGRAM_ID = new PublicKey("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+const ASSOCIATED_TOKEN_PROGRAM_ID = new PublicKey("ATokenGPvbdGVxr1b2hvZbsWLuSBZrojSyRXWGx4fAd");
+const MINT_ACCOUNT_LEN = 82;
+// Accepts any UTF-8 data signed by all its accounts, so it stands in for an outcome hook.
+const MEMO_PROGRAM_ID = new PublicKey("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr");
 const RPC_URL = process.env.ANCHOR_PROVIDER_URL || "https://api.devnet.solana.com";
 const ZK_MERKLE_DEPTH = 16;
 const ZK_CHUNK_SIZE = 16;
 const ZK_CIRCUIT_DIR = path.resolve(process.cwd(), "../zk/noir/vote_eligibility");
 const ZK_TARGET_DIR = path.join(ZK_CIRCUIT_DIR, "target");
+const CLAIM_CIRCUIT_DIR = path.resolve(process.cwd(), "../zk/noir/claim_eligibility");
+// "claim" as a big-endian field, as in the claim circuit.
+const CLAIM_DOMAIN = new BN(Buffer.from("claim"));
 const execFileAsync = promisify(execFile);
 let poseidonHasherPromise: Promise<any> | null = null;
 
@@ -61,6 +73,81 @@ function findNullifierPda(campaignId: string, nullifier: Uint8Array): [PublicKey
   );
 }
 
+function findDelegationPda(campaignId: string, delegateNullifier: Uint8Array): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("delegation"), Buffer.from(getCampaignIdBytes(campaignId)), Buffer.from(delegateNullifier)],
+    PROGRAM_ID
+  );
+}
+
+function findEscrowPda(campaignId: string): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("escrow"), Buffer.from(getCampaignIdBytes(campaignId))],
+    PROGRAM_ID
+  );
+}
+
+function findCampaignPda(campaignId: string): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("campaign"), Buffer.from(getCampaignIdBytes(campaignId))],
+    PROGRAM_ID
+  );
+}
+
+function findClaimNullifierPda(campaignId: string, nullifier: Uint8Array): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("claim_nullifier"), Buffer.from(getCampaignIdBytes(campaignId)), Buffer.from(nullifier)],
+    PROGRAM_ID
+  );
+}
+
+function findClaimLogPda(campaignId: string, authority: PublicKey): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("claim_log"), Buffer.from(getCampaignIdBytes(campaignId)), authority.toBuffer()],
+    PROGRAM_ID
+  );
+}
+
+function findClaimRecordPda(campaignId: string, nullifier: Uint8Array): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("claim_record"), Buffer.from(getCampaignIdBytes(campaignId)), Buffer.from(nullifier)],
+    PROGRAM_ID
+  );
+}
+
+function findAssociatedTokenAddress(owner: PublicKey, mint: PublicKey): PublicKey {
+  return PublicKey.findProgramAddressSync(
+    [owner.toBuffer(), TOKEN_PROGRAM_ID.toBuffer(), mint.toBuffer()],
+    ASSOCIATED_TOKEN_PROGRAM_ID
+  )[0];
+}
+
+function poolOptions(overrides: Record<string, unknown> = {}): Record<string, unknown> {
+  return {
+    encryptTurnout: false,
+    revealMode: { tallies: {} },
+    minVotes: new BN(0),
+    publicReveal: false,
+    weighted: false,
+    allowRevote: false,
+    ballotType: { singleChoice: {} },
+    optionCount: 0,
+    questionCount: 1,
+    eligibleCount: new BN(0),
+    appealBond: new BN(0),
+    maxAppealRounds: 0,
+    outcomePolicy: {
+      tieBreak: { tie: {} },
+      minMargin: new BN(0),
+      quorumBps: 0,
+      quorumDefault: { refundHost: {} },
+    },
+    outcomeHookProgram: PublicKey.default,
+    outcomeHookDiscriminator: Array(8).fill(0),
+    ...overrides,
+  };
+}
+
 function findAllowancePda(handle: bigint, allowedAddress: PublicKey): [PublicKey, number] {
   const handleBuffer = Buffer.alloc(16);
   let h = handle;
@@ -158,23 +245,29 @@ function resolveBin(name: string, fallbackPaths: string[]): string {
   return name;
 }
 
-async function ensureZkArtifacts(nargoBin: string, sunspotBin: string): Promise<void> {
-  if (!existsSync(ZK_CIRCUIT_DIR)) {
-    throw new Error(`ZK circuit directory not found: ${ZK_CIRCUIT_DIR}`);
+async function ensureZkArtifacts(
+  nargoBin: string,
+  sunspotBin: string,
+  circuitDir: string = ZK_CIRCUIT_DIR,
+  circuit: string = "vote_eligibility"
+): Promise<void> {
+  if (!existsSync(circuitDir)) {
+    throw new Error(`ZK circuit directory not found: ${circuitDir}`);
   }
 
-  const jsonPath = path.join(ZK_TARGET_DIR, "vote_eligibility.json");
-  const ccsPath = path.join(ZK_TARGET_DIR, "vote_eligibility.ccs");
-  const pkPath = path.join(ZK_TARGET_DIR, "vote_eligibility.pk");
+  const targetDir = path.join(circuitDir, "target");
+  const jsonPath = path.join(targetDir, `${circuit}.json`);
+  const ccsPath = path.join(targetDir, `${circuit}.ccs`);
+  const pkPath = path.join(targetDir, `${circuit}.pk`);
 
   if (!existsSync(jsonPath)) {
-    await execFileAsync(nargoBin, ["compile"], { cwd: ZK_CIRCUIT_DIR });
+    await execFileAsync(nargoBin, ["compile"], { cwd: circuitDir });
   }
   if (!existsSync(ccsPath)) {
-    await execFileAsync(sunspotBin, ["compile", jsonPath], { cwd: ZK_CIRCUIT_DIR });
+    await execFileAsync(sunspotBin, ["compile", jsonPath], { cwd: circuitDir });
   }
   if (!existsSync(pkPath)) {
-    await execFileAsync(sunspotBin, ["setup", ccsPath], { cwd: ZK_CIRCUIT_DIR });
+    await execFileAsync(sunspotBin, ["setup", ccsPath], { cwd: circuitDir });
   }
 }
 
@@ -203,6 +296,15 @@ function extractHandle(anchorHandle: any): bigint {
   return BigInt(0);
 }
 
+// Anchor surfaces program errors as `error.errorCode.code`; constraint failures
+// thrown before the handler runs only show up in the logs.
+async function assertProgramError(promise: Promise<unknown>, code: string): Promise<void> {
+  await assert.rejects(promise, (error: any) => {
+    const message = `${error?.error?.errorCode?.code ?? ""} ${String(error)} ${(error?.logs ?? []).join("\n")}`;
+    return message.includes(code);
+  });
+}
+
 async function sleep(ms: number): Promise<void> {
   await new Promise((resolve) => setTimeout(resolve, ms));
 }
@@ -270,6 +372,304 @@ describe("chameo-privacy integration", () => {
     throw new Error(`${label} ${pubkey.toBase58()} needs ${formatSol(needed)} SOL (min ${formatSol(minLamports)} SOL)`);
   }
 
+  function uniqueCampaignId(prefix: string): string {
+    return `${prefix}-${Date.now()}-${Math.floor(Math.random() * 1_000_000)}`;
+  }
+
+  async function chainTime(): Promise<number> {
+    const time = await connection.getBlockTime(await connection.getSlot("confirmed"));
+    if (time === null) throw new Error("block time unavailable");
+    return time;
+  }
+
+  async function waitUntilChainTime(unixTime: number): Promise<void> {
+    while ((await chainTime()) <= unixTime) {
+      await sleep(2000);
+    }
+  }
+
+  async function transferLamports(to: PublicKey, lamports: number): Promise<void> {
+    const tx = new anchor.web3.Transaction().add(
+      SystemProgram.transfer({ fromPubkey: walletKeypair.publicKey, toPubkey: to, lamports })
+    );
+    await provider.sendAndConfirm(tx);
+  }
+
+  async function initializeSolEscrow(
+    id: string,
+    refundAddress: PublicKey,
+    terms: { payoutAmount?: number; maxClaims?: number; expiresAt: number; winnersDeadline?: number }
+  ): Promise<PublicKey> {
+    const [escrow] = findEscrowPda(id);
+    await program.methods
+      .initializeEscrow(
+        getCampaignIdBytes(id),
+        refundAddress,
+        new BN(terms.payoutAmount ?? 1_000_000),
+        terms.maxClaims ?? 10,
+        new BN(terms.expiresAt),
+        new BN(terms.winnersDeadline ?? 0)
+      )
+      .accounts({
+        escrow,
+        authority: walletKeypair.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    return escrow;
+  }
+
+  async function fundSolEscrow(id: string, amount: number): Promise<void> {
+    await program.methods
+      .fundEscrow(getCampaignIdBytes(id), new BN(amount))
+      .accounts({
+        escrow: findEscrowPda(id)[0],
+        funder: walletKeypair.publicKey,
+        funderTokenAccount: null,
+        vault: null,
+        mint: null,
+        tokenProgram: null,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+  }
+
+  // Hand-built SPL Token instructions, so the tests need no token client.
+  async function createMint(decimals: number): Promise<PublicKey> {
+    const mint = Keypair.generate();
+    const lamports = await connection.getMinimumBalanceForRentExemption(MINT_ACCOUNT_LEN);
+    const initializeMint2 = Buffer.concat([
+      Buffer.from([20, decimals]),
+      walletKeypair.publicKey.toBuffer(),
+      Buffer.from([0]),
+    ]);
+    const tx = new anchor.web3.Transaction().add(
+      SystemProgram.createAccount({
+        fromPubkey: walletKeypair.publicKey,
+        newAccountPubkey: mint.publicKey,
+        lamports,
+        space: MINT_ACCOUNT_LEN,
+        programId: TOKEN_PROGRAM_ID,
+      }),
+      new anchor.web3.TransactionInstruction({
+        programId: TOKEN_PROGRAM_ID,
+        keys: [{ pubkey: mint.publicKey, isSigner: false, isWritable: true }],
+        data: initializeMint2,
+      })
+    );
+    await provider.sendAndConfirm(tx, [mint]);
+    return mint.publicKey;
+  }
+
+  async function createTokenAccount(owner: PublicKey, mint: PublicKey): Promise<PublicKey> {
+    const account = findAssociatedTokenAddress(owner, mint);
+    const tx = new anchor.web3.Transaction().add(
+      new anchor.web3.TransactionInstruction({
+        programId: ASSOCIATED_TOKEN_PROGRAM_ID,
+        keys: [
+          { pubkey: walletKeypair.publicKey, isSigner: true, isWritable: true },
+          { pubkey: account, isSigner: false, isWritable: true },
+          { pubkey: owner, isSigner: false, isWritable: false },
+          { pubkey: mint, isSigner: false, isWritable: false },
+          { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+          { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+        ],
+        data: Buffer.from([1]),
+      })
+    );
+    await provider.sendAndConfirm(tx);
+    return account;
+  }
+
+  async function mintTo(mint: PublicKey, destination: PublicKey, amount: number): Promise<void> {
+    const data = Buffer.alloc(9);
+    data.writeUInt8(7, 0);
+    data.writeBigUInt64LE(BigInt(amount), 1);
+    const tx = new anchor.web3.Transaction().add(
+      new anchor.web3.TransactionInstruction({
+        programId: TOKEN_PROGRAM_ID,
+        keys: [
+          { pubkey: mint, isSigner: false, isWritable: true },
+          { pubkey: destination, isSigner: false, isWritable: true },
+          { pubkey: walletKeypair.publicKey, isSigner: true, isWritable: false },
+        ],
+        data,
+      })
+    );
+    await provider.sendAndConfirm(tx);
+  }
+
+  async function tokenBalance(account: PublicKey): Promise<number> {
+    return Number((await connection.getTokenAccountBalance(account)).value.amount);
+  }
+
+  async function initializePool(
+    id: string,
+    options: Record<string, unknown>,
+    authority: Keypair = walletKeypair
+  ): Promise<PublicKey> {
+    const [pool] = findVotingPoolPda(id);
+    const builder = program.methods
+      .initializeVotingPool(getCampaignIdBytes(id), Array.from(eligibilityRoot), ZK_VERIFIER_PROGRAM_ID, options)
+      .accounts({
+        votingPool: pool,
+        campaign: findCampaignPda(id)[0],
+        authority: authority.publicKey,
+        incoLightningProgram: INCO_LIGHTNING_ID,
+        systemProgram: SystemProgram.programId,
+      });
+    await (authority === walletKeypair ? builder : builder.signers([authority])).rpc();
+    return pool;
+  }
+
+  // A pool below `minVotes` closes void, on its policy's quorum default, without decrypting anything.
+  async function closeVoid(id: string): Promise<void> {
+    await program.methods
+      .closeVoting(getCampaignIdBytes(id), walletKeypair.publicKey)
+      .accounts({
+        votingPool: findVotingPoolPda(id)[0],
+        authority: walletKeypair.publicKey,
+        allowedAddress: walletKeypair.publicKey,
+        allowanceRefund: null,
+        allowanceEqual: null,
+        allowanceTurnout: null,
+        incoLightningProgram: INCO_LIGHTNING_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+  }
+
+  function refundSolEscrow(id: string, refundAddress: PublicKey, pool: PublicKey | null): Promise<string> {
+    return program.methods
+      .refundEscrow(getCampaignIdBytes(id))
+      .accounts({
+        escrow: findEscrowPda(id)[0],
+        refundAddress,
+        refundTokenAccount: null,
+        votingPool: pool,
+        requester: walletKeypair.publicKey,
+        vault: null,
+        mint: null,
+        tokenProgram: null,
+      })
+      .rpc();
+  }
+
+  function claimSol(
+    id: string,
+    nullifier: Buffer,
+    recipient: PublicKey,
+    proof: Buffer,
+    publicWitness: Buffer,
+    verifier: PublicKey = CLAIM_VERIFIER_PROGRAM_ID
+  ): Promise<string> {
+    return program.methods
+      .claimZk(getCampaignIdBytes(id), Array.from(nullifier), proof, publicWitness)
+      .accounts({
+        escrow: findEscrowPda(id)[0],
+        claimNullifier: findClaimNullifierPda(id, nullifier)[0],
+        recipient,
+        recipientTokenAccount: null,
+        payer: walletKeypair.publicKey,
+        zkVerifierProgram: verifier,
+        vault: null,
+        mint: null,
+        tokenProgram: null,
+        systemProgram: SystemProgram.programId,
+      })
+      .preInstructions([ComputeBudgetProgram.setComputeUnitLimit({ units: 1_400_000 })])
+      .rpc();
+  }
+
+  function recordClaim(
+    id: string,
+    nullifier: Buffer,
+    maxClaims: number,
+    authority: Keypair = walletKeypair
+  ): Promise<string> {
+    const builder = program.methods
+      .recordClaim(getCampaignIdBytes(id), Array.from(nullifier), maxClaims)
+      .accounts({
+        claimLog: findClaimLogPda(id, authority.publicKey)[0],
+        escrow: findEscrowPda(id)[0],
+        claimRecord: findClaimRecordPda(id, nullifier)[0],
+        authority: authority.publicKey,
+        systemProgram: SystemProgram.programId,
+      });
+    return (authority === walletKeypair ? builder : builder.signers([authority])).rpc();
+  }
+
+  // A campaign whose dispute closes void on an equal split, so it settles without votes.
+  async function openVoidDispute(id: string, maxAppealRounds: number): Promise<PublicKey> {
+    const idBytes = getCampaignIdBytes(id);
+    const [campaign] = findCampaignPda(id);
+    const [pool] = findVotingPoolPda(id);
+    const winnersDeadline = (await chainTime()) + 10;
+    await initializeSolEscrow(id, deriveKeypair(`refund-${id}`).publicKey, {
+      expiresAt: winnersDeadline + 3600,
+      winnersDeadline,
+    });
+    await fundSolEscrow(id, 9_000_000);
+    await program.methods
+      .initializeCampaign(
+        idBytes,
+        Array.from(eligibilityRoot),
+        ZK_VERIFIER_PROGRAM_ID,
+        poolOptions({
+          minVotes: new BN(1),
+          eligibleCount: new BN(3),
+          maxAppealRounds,
+          outcomePolicy: {
+            tieBreak: { tie: {} },
+            minMargin: new BN(0),
+            quorumBps: 0,
+            quorumDefault: { equalDistribution: {} },
+          },
+        }),
+        new BN(winnersDeadline),
+        new BN(0)
+      )
+      .accounts({
+        campaign,
+        votingPool: pool,
+        authority: walletKeypair.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    await waitUntilChainTime(winnersDeadline);
+    // The authority opens the dispute without a proof.
+    await program.methods
+      .openDispute(idBytes, Array(32).fill(0), Buffer.alloc(0), Buffer.alloc(0))
+      .accounts({
+        campaign,
+        votingPool: pool,
+        opener: walletKeypair.publicKey,
+        zkVerifierProgram: ZK_VERIFIER_PROGRAM_ID,
+        incoLightningProgram: INCO_LIGHTNING_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    return pool;
+  }
+
+  function settleSolEscrow(id: string, pool: PublicKey): Promise<string> {
+    return program.methods
+      .settleEqualDistribution(getCampaignIdBytes(id))
+      .accounts({
+        escrow: findEscrowPda(id)[0],
+        campaign: findCampaignPda(id)[0],
+        votingPool: pool,
+        refundAddress: deriveKeypair(`refund-${id}`).publicKey,
+        refundTokenAccount: null,
+        requester: walletKeypair.publicKey,
+        vault: null,
+        mint: null,
+        tokenProgram: null,
+      })
+      .rpc();
+  }
+
   before(async () => {
     voterA = deriveKeypair("chameo-test-voter-a");
 
@@ -281,7 +681,28 @@ describe("chameo-privacy integration", () => {
 
   it("initializes voting pool and analytics", async () => {
     await program.methods
-      .initializeVotingPool(campaignIdBytes, Array.from(eligibilityRoot), ZK_VERIFIER_PROGRAM_ID)
+      .initializeVotingPool(campaignIdBytes, Array.from(eligibilityRoot), ZK_VERIFIER_PROGRAM_ID, {
+        encryptTurnout: false,
+        revealMode: { tallies: {} },
+        minVotes: new BN(0),
+        publicReveal: false,
+        weighted: false,
+        allowRevote: false,
+        ballotType: { singleChoice: {} },
+        optionCount: 0,
+        questionCount: 1,
+        eligibleCount: new BN(0),
+        appealBond: new BN(0),
+        maxAppealRounds: 0,
+        outcomePolicy: {
+          tieBreak: { tie: {} },
+          minMargin: new BN(0),
+          quorumBps: 0,
+          quorumDefault: { refundHost: {} },
+        },
+        outcomeHookProgram: PublicKey.default,
+        outcomeHookDiscriminator: Array(8).fill(0),
+      })
       .accounts({
         votingPool,
         authority: walletKeypair.publicKey,
@@ -308,6 +729,39 @@ describe("chameo-privacy integration", () => {
     assert.ok(analyticsState.authority.equals(walletKeypair.publicKey));
   });
 
+  it("refuses to migrate a pool that is already current", async () => {
+    await assertProgramError(
+      program.methods
+        .migrateVotingPool(campaignIdBytes)
+        .accounts({
+          votingPool,
+          authority: walletKeypair.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc(),
+      "AlreadyMigrated"
+    );
+
+    const state = await (program.account as any).votingPool.fetch(votingPool);
+    assert.strictEqual(state.version, 1);
+  });
+
+  it("refuses to migrate another authority's pool", async () => {
+    const outsider = deriveKeypair("chameo-test-outsider");
+    await assertProgramError(
+      program.methods
+        .migrateVotingPool(campaignIdBytes)
+        .accounts({
+          votingPool,
+          authority: outsider.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([outsider])
+        .rpc(),
+      "Unauthorized"
+    );
+  });
+
   it("exposes expected IDL structure", () => {
     const grant = (idl as any).instructions.find((ix: any) => ix.name === "grant_analytics_access");
     assert.ok(grant, "grant_analytics_access not found");
@@ -333,6 +787,7 @@ describe("chameo-privacy integration", () => {
     assert.deepStrictEqual(castVoteZkAccounts, [
       "nullifier",
       "voting_pool",
+      "delegation",
       "relayer",
       "zk_verifier_program",
       "inco_lightning_program",
@@ -360,7 +815,28 @@ describe("chameo-privacy integration", () => {
     const { root, siblings, pathBits } = await buildSingleLeafProof(leafHash, ZK_MERKLE_DEPTH);
 
     await program.methods
-      .initializeVotingPool(zkCampaignIdBytes, Array.from(root), ZK_VERIFIER_PROGRAM_ID)
+      .initializeVotingPool(zkCampaignIdBytes, Array.from(root), ZK_VERIFIER_PROGRAM_ID, {
+        encryptTurnout: false,
+        revealMode: { tallies: {} },
+        minVotes: new BN(0),
+        publicReveal: false,
+        weighted: false,
+        allowRevote: false,
+        ballotType: { singleChoice: {} },
+        optionCount: 0,
+        questionCount: 1,
+        eligibleCount: new BN(0),
+        appealBond: new BN(0),
+        maxAppealRounds: 0,
+        outcomePolicy: {
+          tieBreak: { tie: {} },
+          minMargin: new BN(0),
+          quorumBps: 0,
+          quorumDefault: { refundHost: {} },
+        },
+        outcomeHookProgram: PublicKey.default,
+        outcomeHookDiscriminator: Array(8).fill(0),
+      })
       .accounts({
         votingPool: zkVotingPool,
         authority: walletKeypair.publicKey,
@@ -385,6 +861,10 @@ describe("chameo-privacy integration", () => {
       "",
       `ciphertext = ${bufferToArrayString(ciphertext)}`,
       "",
+      `ballot_meta = ${bufferToArrayString(Buffer.alloc(14))}`,
+      "",
+      "approvals = 0",
+      "",
       `merkle_root = "${bufferToFieldString(root)}"`,
       `nullifier = "${bufferToFieldString(nullifier)}"`,
       `commitment = "${bufferToFieldString(commitment)}"`,
@@ -415,6 +895,7 @@ describe("chameo-privacy integration", () => {
       .accounts({
         nullifier: nullifierPda,
         votingPool: zkVotingPool,
+        delegation: null,
         relayer: walletKeypair.publicKey,
         zkVerifierProgram: ZK_VERIFIER_PROGRAM_ID,
         incoLightningProgram: INCO_LIGHTNING_ID,
@@ -460,6 +941,7 @@ describe("chameo-privacy integration", () => {
         allowedAddress: walletKeypair.publicKey,
         allowanceRefund,
         allowanceEqual,
+        allowanceTurnout: null,
         incoLightningProgram: INCO_LIGHTNING_ID,
         systemProgram: SystemProgram.programId,
       })
@@ -537,4 +1019,517 @@ describe("chameo-privacy integration", () => {
     assert.strictEqual(parseInt(plaintexts[1], 10), 1);
     assert.strictEqual(parseInt(plaintexts[2], 10), 1);
   });
+
+  it("holds escrow deposits and refunds the whole balance once expired", async () => {
+    const id = uniqueCampaignId("escrow");
+    const refundAddress = deriveKeypair(`refund-${id}`).publicKey;
+    const expiresAt = (await chainTime()) + 15;
+    const escrow = await initializeSolEscrow(id, refundAddress, { expiresAt });
+
+    await fundSolEscrow(id, 10_000_000);
+    const funded = await (program.account as any).escrow.fetch(escrow);
+    assert.strictEqual(funded.deposited.toNumber(), 10_000_000);
+    assert.ok(funded.authority.equals(walletKeypair.publicKey));
+
+    await assertProgramError(refundSolEscrow(id, refundAddress, null), "EscrowReleaseLocked");
+
+    await waitUntilChainTime(expiresAt);
+    await refundSolEscrow(id, refundAddress, null);
+
+    assert.strictEqual(await connection.getBalance(refundAddress), 10_000_000);
+    const refunded = await (program.account as any).escrow.fetch(escrow);
+    assert.strictEqual(refunded.isClosed, true);
+    assert.strictEqual(refunded.released.toNumber(), 10_000_000);
+
+    await assertProgramError(refundSolEscrow(id, refundAddress, null), "EscrowClosed");
+  });
+
+  it("refuses escrow refunds through a pool the escrow authority doesn't run", async () => {
+    const id = uniqueCampaignId("escrow-forged");
+    const refundAddress = deriveKeypair(`refund-${id}`).publicKey;
+    await initializeSolEscrow(id, refundAddress, { expiresAt: (await chainTime()) + 3600 });
+    await fundSolEscrow(id, 10_000_000);
+
+    // Without a campaign account anyone can open the pool; a void close would then pick its outcome.
+    const outsider = deriveKeypair(`outsider-${id}`);
+    await transferLamports(outsider.publicKey, 50_000_000);
+    const forged = await initializePool(id, poolOptions({ minVotes: new BN(1) }), outsider);
+
+    await assertProgramError(refundSolEscrow(id, refundAddress, forged), "Unauthorized");
+    assert.strictEqual(await connection.getBalance(refundAddress), 0);
+  });
+
+  it("refunds an escrow early only once its dispute can no longer be appealed", async () => {
+    const appealable = uniqueCampaignId("escrow-appealable");
+    const appealableRefund = deriveKeypair(`refund-${appealable}`).publicKey;
+    await initializeSolEscrow(appealable, appealableRefund, { expiresAt: (await chainTime()) + 3600 });
+    await fundSolEscrow(appealable, 10_000_000);
+    const appealablePool = await initializePool(
+      appealable,
+      poolOptions({ minVotes: new BN(1), maxAppealRounds: 1 })
+    );
+
+    await assertProgramError(
+      refundSolEscrow(appealable, appealableRefund, appealablePool),
+      "EscrowReleaseLocked"
+    );
+    await closeVoid(appealable);
+    const closed = await (program.account as any).votingPool.fetch(appealablePool);
+    assert.deepStrictEqual(closed.outcome, { refundHost: {} });
+    assert.ok(closed.closedAt.toNumber() > 0);
+    await assertProgramError(
+      refundSolEscrow(appealable, appealableRefund, appealablePool),
+      "EscrowReleaseLocked"
+    );
+
+    const final = uniqueCampaignId("escrow-final");
+    const finalRefund = deriveKeypair(`refund-${final}`).publicKey;
+    const escrow = await initializeSolEscrow(final, finalRefund, { expiresAt: (await chainTime()) + 3600 });
+    await fundSolEscrow(final, 10_000_000);
+    const finalPool = await initializePool(final, poolOptions({ minVotes: new BN(1) }));
+    await closeVoid(final);
+
+    await refundSolEscrow(final, finalRefund, finalPool);
+    assert.strictEqual(await connection.getBalance(finalRefund), 10_000_000);
+    const state = await (program.account as any).escrow.fetch(escrow);
+    assert.strictEqual(state.isClosed, true);
+  });
+
+  it("holds SPL escrow deposits in the vault and refunds them to the refund token account", async () => {
+    const id = uniqueCampaignId("token-escrow");
+    const idBytes = getCampaignIdBytes(id);
+    const [escrow] = findEscrowPda(id);
+    const refundAddress = deriveKeypair(`refund-${id}`).publicKey;
+    const mint = await createMint(6);
+    const vault = findAssociatedTokenAddress(escrow, mint);
+    const expiresAt = (await chainTime()) + 20;
+
+    await program.methods
+      .initializeTokenEscrow(idBytes, refundAddress, new BN(100_000), 5, new BN(expiresAt), new BN(0))
+      .accounts({
+        escrow,
+        mint,
+        vault,
+        authority: walletKeypair.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    const initialized = await (program.account as any).escrow.fetch(escrow);
+    assert.ok(initialized.mint.equals(mint));
+    assert.ok(initialized.vault.equals(vault));
+    assert.strictEqual(initialized.decimals, 6);
+
+    const funderTokens = await createTokenAccount(walletKeypair.publicKey, mint);
+    await mintTo(mint, funderTokens, 1_000_000);
+    await program.methods
+      .fundEscrow(idBytes, new BN(500_000))
+      .accounts({
+        escrow,
+        funder: walletKeypair.publicKey,
+        funderTokenAccount: funderTokens,
+        vault,
+        mint,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    assert.strictEqual(await tokenBalance(vault), 500_000);
+    const funded = await (program.account as any).escrow.fetch(escrow);
+    assert.strictEqual(funded.deposited.toNumber(), 500_000);
+
+    const refundTokens = await createTokenAccount(refundAddress, mint);
+    await waitUntilChainTime(expiresAt);
+
+    const refund = (refundTokenAccount: PublicKey, withVault: boolean) =>
+      program.methods
+        .refundEscrow(idBytes)
+        .accounts({
+          escrow,
+          refundAddress,
+          refundTokenAccount,
+          votingPool: null,
+          requester: walletKeypair.publicKey,
+          vault: withVault ? vault : null,
+          mint: withVault ? mint : null,
+          tokenProgram: withVault ? TOKEN_PROGRAM_ID : null,
+        })
+        .rpc();
+
+    await assertProgramError(refund(refundTokens, false), "InvalidEscrowVault");
+    await assertProgramError(refund(funderTokens, true), "Unauthorized");
+
+    await refund(refundTokens, true);
+    assert.strictEqual(await tokenBalance(refundTokens), 500_000);
+    assert.strictEqual(await tokenBalance(vault), 0);
+    const refunded = await (program.account as any).escrow.fetch(escrow);
+    assert.strictEqual(refunded.isClosed, true);
+  });
+  it("refuses claims without a claim root or through another verifier", async () => {
+    const id = uniqueCampaignId("claim-unavailable");
+    await initializeSolEscrow(id, deriveKeypair(`refund-${id}`).publicKey, {
+      expiresAt: (await chainTime()) + 3600,
+    });
+    await fundSolEscrow(id, 10_000_000);
+
+    const nullifier = createHash("sha256").update(`claim-nullifier-${id}`).digest();
+    const recipient = deriveKeypair(`claimant-${id}`).publicKey;
+    const proof = Buffer.alloc(388);
+    const publicWitness = Buffer.alloc(108);
+
+    await assertProgramError(
+      claimSol(id, nullifier, recipient, proof, publicWitness, ZK_VERIFIER_PROGRAM_ID),
+      "InvalidZkVerifier"
+    );
+    await assertProgramError(claimSol(id, nullifier, recipient, proof, publicWitness), "ClaimUnavailable");
+  });
+
+  it("pays a claim once per nullifier, only to the proven recipient", async function () {
+    if (CLAIM_VERIFIER_PROGRAM_ID.equals(PublicKey.default)) {
+      // The deployed program has no pinned claim verifier to prove against.
+      this.skip();
+    }
+    const nargoBin = resolveBin("nargo", [path.join(os.homedir(), ".nargo/bin/nargo")]);
+    const sunspotBin = resolveBin("sunspot", [path.join(os.homedir(), ".local/bin/sunspot")]);
+    await ensureZkArtifacts(nargoBin, sunspotBin, CLAIM_CIRCUIT_DIR, "claim_eligibility");
+
+    const id = uniqueCampaignId("claim");
+    const idBytes = getCampaignIdBytes(id);
+    const escrow = await initializeSolEscrow(id, deriveKeypair(`refund-${id}`).publicKey, {
+      payoutAmount: 1_000_000,
+      maxClaims: 2,
+      expiresAt: (await chainTime()) + 3600,
+    });
+    await fundSolEscrow(id, 10_000_000);
+
+    const identityHash = createHash("sha256").update(`claim-identity-${id}`).digest();
+    const leafHash = await hashIdentityLeaf(identityHash);
+    const { root, siblings, pathBits } = await buildSingleLeafProof(leafHash, ZK_MERKLE_DEPTH);
+    await program.methods
+      .setClaimRoot(idBytes, Array.from(root))
+      .accounts({ escrow, authority: walletKeypair.publicKey })
+      .rpc();
+
+    const recipient = deriveKeypair(`claimant-${id}`).publicKey;
+    const leafFields = chunkBytes(identityHash, ZK_CHUNK_SIZE).map((chunk) => new BN(chunk));
+    const nullifier = await poseidonHashFields([...leafFields, CLAIM_DOMAIN]);
+    const recipientField = await poseidonHashBytes(recipient.toBuffer());
+
+    const proverToml = [
+      `leaf = ${bufferToArrayString(identityHash)}`,
+      "",
+      `siblings = [`,
+      siblings.map((sibling) => `  ${bufferToArrayString(sibling)}`).join(",\n"),
+      `]`,
+      "",
+      `path_bits = [${pathBits.join(", ")}]`,
+      "",
+      `wallet = ${bufferToArrayString(recipient.toBuffer())}`,
+      "",
+      `merkle_root = "${bufferToFieldString(root)}"`,
+      `nullifier = "${bufferToFieldString(nullifier)}"`,
+      `recipient = "${bufferToFieldString(recipientField)}"`,
+      "",
+    ].join("\n");
+    writeFileSync(path.join(CLAIM_CIRCUIT_DIR, "Prover.toml"), proverToml);
+    await execFileAsync(nargoBin, ["execute"], { cwd: CLAIM_CIRCUIT_DIR });
+
+    const targetDir = path.join(CLAIM_CIRCUIT_DIR, "target");
+    await execFileAsync(
+      sunspotBin,
+      [
+        "prove",
+        path.join(targetDir, "claim_eligibility.json"),
+        path.join(targetDir, "claim_eligibility.gz"),
+        path.join(targetDir, "claim_eligibility.ccs"),
+        path.join(targetDir, "claim_eligibility.pk"),
+      ],
+      { cwd: CLAIM_CIRCUIT_DIR }
+    );
+    const proof = readFileSync(path.join(targetDir, "claim_eligibility.proof"));
+    const publicWitness = readFileSync(path.join(targetDir, "claim_eligibility.pw"));
+
+    // Public inputs follow a 12-byte header: merkle_root, nullifier, recipient.
+    const otherRoot = Buffer.from(publicWitness);
+    otherRoot.fill(0, 12, 44);
+    await assertProgramError(claimSol(id, nullifier, recipient, proof, otherRoot), "MerkleRootMismatch");
+    const relayer = deriveKeypair(`relayer-${id}`).publicKey;
+    await assertProgramError(claimSol(id, nullifier, relayer, proof, publicWitness), "RecipientMismatch");
+
+    await claimSol(id, nullifier, recipient, proof, publicWitness);
+    assert.strictEqual(await connection.getBalance(recipient), 1_000_000);
+    const paid = await (program.account as any).escrow.fetch(escrow);
+    assert.strictEqual(paid.claimCount, 1);
+    assert.strictEqual(paid.released.toNumber(), 1_000_000);
+    const spent = await (program.account as any).claimNullifier.fetch(findClaimNullifierPda(id, nullifier)[0]);
+    assert.ok(spent.recipient.equals(recipient));
+
+    // The spent nullifier's account already exists, so the same proof can't pay twice.
+    await assertProgramError(claimSol(id, nullifier, recipient, proof, publicWitness), "already in use");
+    assert.strictEqual(await connection.getBalance(recipient), 1_000_000);
+  });
+  it("records off-chain claims once each, up to the limit the log opened with", async () => {
+    const id = uniqueCampaignId("record-claim");
+    const nullifiers = [0, 1, 2].map((i) => createHash("sha256").update(`record-${id}-${i}`).digest());
+    const [claimLog] = findClaimLogPda(id, walletKeypair.publicKey);
+
+    await recordClaim(id, nullifiers[0], 2);
+    const opened = await (program.account as any).claimLog.fetch(claimLog);
+    assert.ok(opened.authority.equals(walletKeypair.publicKey));
+    assert.strictEqual(opened.maxClaims, 2);
+    assert.strictEqual(opened.claimCount, 1);
+    const record = await (program.account as any).claimRecord.fetch(findClaimRecordPda(id, nullifiers[0])[0]);
+    assert.strictEqual(record.index, 0);
+
+    await assertProgramError(recordClaim(id, nullifiers[0], 2), "already in use");
+    await assertProgramError(recordClaim(id, nullifiers[1], 3), "ClaimUnavailable");
+    await recordClaim(id, nullifiers[1], 2);
+    await assertProgramError(recordClaim(id, nullifiers[2], 2), "ClaimLimitReached");
+
+    // Another signer gets its own log, leaving the first one as it was.
+    const outsider = deriveKeypair(`outsider-${id}`);
+    await transferLamports(outsider.publicKey, 20_000_000);
+    await recordClaim(id, nullifiers[2], 1, outsider);
+    const outsiderLog = await (program.account as any).claimLog.fetch(findClaimLogPda(id, outsider.publicKey)[0]);
+    assert.strictEqual(outsiderLog.claimCount, 1);
+    const hostLog = await (program.account as any).claimLog.fetch(claimLog);
+    assert.strictEqual(hostLog.claimCount, 2);
+    assert.strictEqual(hostLog.maxClaims, 2);
+  });
+
+  it("records claims against an escrow only for its authority, at the escrow's limit", async () => {
+    const id = uniqueCampaignId("record-escrow-claim");
+    await initializeSolEscrow(id, deriveKeypair(`refund-${id}`).publicKey, {
+      maxClaims: 1,
+      expiresAt: (await chainTime()) + 3600,
+    });
+    const nullifiers = [0, 1].map((i) => createHash("sha256").update(`record-${id}-${i}`).digest());
+
+    const outsider = deriveKeypair(`outsider-${id}`);
+    await transferLamports(outsider.publicKey, 20_000_000);
+    await assertProgramError(recordClaim(id, nullifiers[0], 1, outsider), "Unauthorized");
+
+    await recordClaim(id, nullifiers[0], 5);
+    const log = await (program.account as any).claimLog.fetch(findClaimLogPda(id, walletKeypair.publicKey)[0]);
+    assert.strictEqual(log.maxClaims, 1);
+    await assertProgramError(recordClaim(id, nullifiers[1], 5), "ClaimLimitReached");
+  });
+  it("splits an escrow evenly only after its opened dispute finalizes on equal distribution", async function () {
+    if (CLAIM_VERIFIER_PROGRAM_ID.equals(PublicKey.default)) {
+      // Without a pinned claim verifier, settlement is refused before anything else.
+      this.skip();
+    }
+    const id = uniqueCampaignId("settle");
+    const [escrow] = findEscrowPda(id);
+
+    // A campaign whose dispute was never opened can't settle, even through another campaign's pool.
+    const unopened = uniqueCampaignId("settle-unopened");
+    const unopenedDeadline = (await chainTime()) + 3600;
+    await initializeSolEscrow(unopened, deriveKeypair(`refund-${unopened}`).publicKey, {
+      expiresAt: unopenedDeadline + 3600,
+      winnersDeadline: unopenedDeadline,
+    });
+    await program.methods
+      .initializeCampaign(
+        getCampaignIdBytes(unopened),
+        Array.from(eligibilityRoot),
+        ZK_VERIFIER_PROGRAM_ID,
+        poolOptions({ eligibleCount: new BN(3) }),
+        new BN(unopenedDeadline),
+        new BN(0)
+      )
+      .accounts({
+        campaign: findCampaignPda(unopened)[0],
+        votingPool: findVotingPoolPda(unopened)[0],
+        authority: walletKeypair.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    const pool = await openVoidDispute(id, 0);
+    await assertProgramError(settleSolEscrow(unopened, pool), "DisputeUnavailable");
+    await assertProgramError(settleSolEscrow(id, pool), "EscrowReleaseLocked");
+
+    await closeVoid(id);
+    await settleSolEscrow(id, pool);
+    const settled = await (program.account as any).escrow.fetch(escrow);
+    assert.strictEqual(settled.equalDistributionSettled, true);
+    assert.strictEqual(settled.payoutAmount.toNumber(), 3_000_000);
+    assert.strictEqual(settled.maxClaims, 3);
+    assert.deepStrictEqual(Buffer.from(settled.claimRoot), eligibilityRoot);
+
+    await assertProgramError(settleSolEscrow(id, pool), "ClaimUnavailable");
+  });
+
+  it("holds an equal split while the dispute can still be appealed", async function () {
+    if (CLAIM_VERIFIER_PROGRAM_ID.equals(PublicKey.default)) {
+      this.skip();
+    }
+    const id = uniqueCampaignId("settle-appealable");
+    const pool = await openVoidDispute(id, 1);
+    await closeVoid(id);
+    const closed = await (program.account as any).votingPool.fetch(pool);
+    assert.deepStrictEqual(closed.outcome, { equalDistribution: {} });
+
+    await assertProgramError(settleSolEscrow(id, pool), "EscrowReleaseLocked");
+    const escrow = await (program.account as any).escrow.fetch(findEscrowPda(id)[0]);
+    assert.strictEqual(escrow.equalDistributionSettled, false);
+  });
+  it("executes an outcome hook only once the outcome can no longer be appealed", async () => {
+    const execute = (id: string) =>
+      program.methods
+        .executeOutcome(getCampaignIdBytes(id))
+        .accounts({
+          votingPool: findVotingPoolPda(id)[0],
+          hookProgram: MEMO_PROGRAM_ID,
+          requester: walletKeypair.publicKey,
+        })
+        .rpc();
+
+    const appealable = uniqueCampaignId("hook-appealable");
+    const appealablePool = await initializePool(
+      appealable,
+      poolOptions({ minVotes: new BN(1), maxAppealRounds: 1, outcomeHookProgram: MEMO_PROGRAM_ID })
+    );
+    await closeVoid(appealable);
+    await assertProgramError(execute(appealable), "OutcomeUnavailable");
+    const held = await (program.account as any).votingPool.fetch(appealablePool);
+    assert.strictEqual(held.outcomeExecuted, false);
+
+    const final = uniqueCampaignId("hook-final");
+    const finalPool = await initializePool(
+      final,
+      poolOptions({ minVotes: new BN(1), outcomeHookProgram: MEMO_PROGRAM_ID })
+    );
+    await closeVoid(final);
+    await execute(final);
+    const executed = await (program.account as any).votingPool.fetch(finalPool);
+    assert.strictEqual(executed.outcomeExecuted, true);
+    await assertProgramError(execute(final), "OutcomeAlreadyExecuted");
+  });
+  it("refuses delegations in revote pools, whose recasts carry no delegated weight", async () => {
+    const id = uniqueCampaignId("delegate-revote");
+    const pool = await initializePool(id, poolOptions({ allowRevote: true }));
+    const nullifier = createHash("sha256").update(`delegator-${id}`).digest();
+    const delegateNullifier = createHash("sha256").update(`delegate-${id}`).digest();
+
+    await assertProgramError(
+      program.methods
+        .delegateVote(
+          getCampaignIdBytes(id),
+          Array.from(nullifier),
+          Array.from(delegateNullifier),
+          Buffer.alloc(388),
+          Buffer.alloc(108)
+        )
+        .accounts({
+          nullifier: findNullifierPda(id, nullifier)[0],
+          delegation: findDelegationPda(id, delegateNullifier)[0],
+          delegateNullifierAccount: findNullifierPda(id, delegateNullifier)[0],
+          delegatorDelegation: findDelegationPda(id, nullifier)[0],
+          votingPool: pool,
+          relayer: walletKeypair.publicKey,
+          zkVerifierProgram: ZK_VERIFIER_PROGRAM_ID,
+          incoLightningProgram: INCO_LIGHTNING_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc(),
+      "DelegationUnavailable"
+    );
+    assert.strictEqual(await connection.getAccountInfo(findDelegationPda(id, delegateNullifier)[0]), null);
+  });
+  it("lets an appellant open an appeal round alone, under the parent's authority", async () => {
+    const id = uniqueCampaignId("appeal-alone");
+    const parentPool = await initializePool(
+      id,
+      poolOptions({ minVotes: new BN(1), maxAppealRounds: 1, appealBond: new BN(1_000_000) })
+    );
+    await closeVoid(id);
+
+    const appellant = Keypair.generate();
+    await transferLamports(appellant.publicKey, 0.05 * LAMPORTS_PER_SOL);
+    const parentIdBytes = getCampaignIdBytes(id);
+    const roundIdBytes = Array.from(
+      createHash("sha256").update("appeal").update(Buffer.from(parentIdBytes)).update(Buffer.from([1])).digest()
+    );
+    const [roundPool] = PublicKey.findProgramAddressSync(
+      [Buffer.from("voting_pool"), Buffer.from(roundIdBytes)],
+      program.programId
+    );
+    const appeal = (windowSeconds: number) =>
+      program.methods
+        .appealVoting(parentIdBytes, roundIdBytes, new BN(windowSeconds))
+        .accounts({
+          parentPool,
+          votingPool: roundPool,
+          appellant: appellant.publicKey,
+          incoLightningProgram: INCO_LIGHTNING_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([appellant])
+        .rpc();
+
+    await assertProgramError(appeal(60), "InvalidPoolOptions");
+    await appeal(3 * 24 * 60 * 60);
+
+    const parent = await (program.account as any).votingPool.fetch(parentPool);
+    const round = await (program.account as any).votingPool.fetch(roundPool);
+    assert.ok(parent.appealPool.equals(roundPool));
+    assert.ok(round.authority.equals(walletKeypair.publicKey));
+    assert.ok(round.appellant.equals(appellant.publicKey));
+    assert.deepStrictEqual(Buffer.from(round.eligibilityRoot), Buffer.from(parent.eligibilityRoot));
+    assert.ok(round.zkVerifierProgram.equals(parent.zkVerifierProgram));
+  });
+  it("refuses ballots between a jury commitment and its draw", async () => {
+    const id = uniqueCampaignId("jury-pending");
+    const pool = await initializePool(id, poolOptions({ eligibleCount: new BN(4) }));
+    const idBytes = getCampaignIdBytes(id);
+    const [jury] = PublicKey.findProgramAddressSync([Buffer.from("jury"), Buffer.from(idBytes)], program.programId);
+    const seed = createHash("sha256").update(`seed-${id}`).digest();
+    await program.methods
+      .commitJurySeed(idBytes, Array.from(createHash("sha256").update(seed).digest()), 2, ZK_VERIFIER_PROGRAM_ID)
+      .accounts({
+        votingPool: pool,
+        jury,
+        authority: walletKeypair.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    const committed = await (program.account as any).votingPool.fetch(pool);
+    assert.strictEqual(committed.juryPending, true);
+
+    const nullifier = createHash("sha256").update(`voter-${id}`).digest();
+    await assertProgramError(
+      program.methods
+        .castVoteZk(idBytes, Array.from(nullifier), Buffer.alloc(388), Buffer.alloc(108), Buffer.alloc(114))
+        .accounts({
+          nullifier: findNullifierPda(id, nullifier)[0],
+          votingPool: pool,
+          delegation: null,
+          relayer: walletKeypair.publicKey,
+          zkVerifierProgram: ZK_VERIFIER_PROGRAM_ID,
+          incoLightningProgram: INCO_LIGHTNING_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc(),
+      "JuryDrawPending"
+    );
+
+    // The draw is still possible, so nobody can abandon it yet.
+    await assertProgramError(
+      program.methods
+        .abandonJurySeed(idBytes)
+        .accounts({
+          votingPool: pool,
+          jury,
+          requester: walletKeypair.publicKey,
+          slotHashes: anchor.web3.SYSVAR_SLOT_HASHES_PUBKEY,
+        })
+        .rpc(),
+      "JuryDrawPending"
+    );
+  });
 });
@@ -17,6 +17,7 @@ export const env = {
   },
   zk: {
     verifierProgramId: process.env.ZK_VERIFIER_PROGRAM_ID || "",
+    claimVerifierProgramId: process.env.ZK_CLAIM_VERIFIER_PROGRAM_ID || "",
     merkleDepth: parseInt(process.env.ZK_MERKLE_DEPTH || "16", 10),
     ciphertextLength: parseInt(process.env.ZK_CIPHERTEXT_LENGTH || "114", 10),
     proofLength: parseInt(process.env.ZK_PROOF_LENGTH || "388", 10),
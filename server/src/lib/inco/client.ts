@@ -1,9 +1,11 @@
-import { PublicKey, Keypair, SystemProgram, ComputeBudgetProgram } from "@solana/web3.js";
-import { Program, AnchorProvider, Wallet } from "@coral-xyz/anchor";
+import { PublicKey, Keypair, SystemProgram, ComputeBudgetProgram, SYSVAR_SLOT_HASHES_PUBKEY } from "@solana/web3.js";
+import { Program, AnchorProvider, Wallet, BN } from "@coral-xyz/anchor";
 import { devnetConnection, env } from "@/config";
+import { ConflictError } from "@/shared";
 import { encryptValue } from "@inco/solana-sdk/encryption";
 import { decrypt } from "@inco/solana-sdk/attested-decrypt";
 import nacl from "tweetnacl";
+import { createHash } from "crypto";
 
 const PROGRAM_ID = new PublicKey(env.inco.programId || "GvoS27ShvsjMoWumJnHnuLbCZpHSS8k36uJFzuctvQtU");
 const INCO_LIGHTNING_ID = new PublicKey("5sjEbPiqgZrYwR31ahR6Uk9wf5awoX61YGg7jExQSwaj");
@@ -85,6 +87,16 @@ export function findVotingPoolPda(campaignId: string): [PublicKey, number] {
   return PublicKey.findProgramAddressSync([Buffer.from("voting_pool"), Buffer.from(getCampaignIdBytes(campaignId))], PROGRAM_ID);
 }
 
+// Appeal rounds are pools keyed by sha256("appeal" || parent campaign id || round).
+export function getAppealCampaignIdBytes(parentCampaignIdBytes: number[], round: number): number[] {
+  const digest = createHash("sha256")
+    .update(Buffer.from("appeal"))
+    .update(Buffer.from(parentCampaignIdBytes))
+    .update(Buffer.from([round]))
+    .digest();
+  return Array.from(digest);
+}
+
 export function findNullifierPda(campaignId: string, nullifier: Uint8Array): [PublicKey, number] {
   if (nullifier.length !== 32) {
     throw new Error("Nullifier must be 32 bytes");
@@ -95,6 +107,16 @@ export function findNullifierPda(campaignId: string, nullifier: Uint8Array): [Pu
   );
 }
 
+export function findDelegationPda(campaignId: string, delegateNullifier: Uint8Array): [PublicKey, number] {
+  if (delegateNullifier.length !== 32) {
+    throw new Error("Nullifier must be 32 bytes");
+  }
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("delegation"), Buffer.from(getCampaignIdBytes(campaignId)), Buffer.from(delegateNullifier)],
+    PROGRAM_ID
+  );
+}
+
 export function findAnalyticsPda(campaignId: string): [PublicKey, number] {
   return PublicKey.findProgramAddressSync([Buffer.from("analytics"), Buffer.from(getCampaignIdBytes(campaignId))], PROGRAM_ID);
 }
@@ -110,16 +132,86 @@ export function findAllowancePda(handle: bigint, allowedAddress: PublicKey): [Pu
   return PublicKey.findProgramAddressSync([handleBuffer, allowedAddress.toBuffer()], INCO_LIGHTNING_ID);
 }
 
-export async function initializeVotingPool(campaignId: string, eligibilityRoot: Buffer, zkVerifierProgram: PublicKey): Promise<string> {
+export type VotingPoolOptions = {
+  encryptTurnout?: boolean;
+  revealMode?: "tallies" | "outcomeOnly";
+  minVotes?: number;
+  publicReveal?: boolean;
+  weighted?: boolean;
+  allowRevote?: boolean;
+  ballotType?: "singleChoice" | "approval" | "quadratic";
+  optionCount?: number;
+  questionCount?: number;
+  eligibleCount?: number;
+  appealBond?: number;
+  maxAppealRounds?: number;
+  outcomePolicy?: OutcomePolicy;
+  outcomeHook?: { program: PublicKey; discriminator: Buffer };
+};
+
+export type PolicyOutcome = "refundHost" | "equalDistribution" | "tie";
+
+// Mirrors the program's OutcomePolicy; the defaults keep the old tie handling.
+export type OutcomePolicy = {
+  tieBreak: PolicyOutcome;
+  minMargin: number;
+  quorumBps: number;
+  quorumDefault: PolicyOutcome;
+};
+
+export const DEFAULT_OUTCOME_POLICY: OutcomePolicy = {
+  tieBreak: "tie",
+  minMargin: 0,
+  quorumBps: 0,
+  quorumDefault: "refundHost",
+};
+
+function toPoolOptionsArg(options: VotingPoolOptions) {
+  return {
+    encryptTurnout: options.encryptTurnout ?? false,
+    revealMode: { [options.revealMode ?? "tallies"]: {} },
+    minVotes: new BN(options.minVotes ?? 0),
+    publicReveal: options.publicReveal ?? false,
+    weighted: options.weighted ?? false,
+    allowRevote: options.allowRevote ?? false,
+    ballotType: { [options.ballotType ?? "singleChoice"]: {} },
+    optionCount: options.optionCount ?? 0,
+    questionCount: options.questionCount ?? 1,
+    eligibleCount: new BN(options.eligibleCount ?? 0),
+    appealBond: new BN(options.appealBond ?? 0),
+    maxAppealRounds: options.maxAppealRounds ?? 0,
+    outcomePolicy: toOutcomePolicyArg(options.outcomePolicy ?? DEFAULT_OUTCOME_POLICY),
+    outcomeHookProgram: options.outcomeHook?.program ?? PublicKey.default,
+    outcomeHookDiscriminator: Array.from(options.outcomeHook?.discriminator ?? Buffer.alloc(8)),
+  };
+}
+
+function toOutcomePolicyArg(policy: OutcomePolicy) {
+  return {
+    tieBreak: { [policy.tieBreak]: {} },
+    minMargin: new BN(policy.minMargin),
+    quorumBps: policy.quorumBps,
+    quorumDefault: { [policy.quorumDefault]: {} },
+  };
+}
+
+export async function initializeVotingPool(
+  campaignId: string,
+  eligibilityRoot: Buffer,
+  zkVerifierProgram: PublicKey,
+  options: VotingPoolOptions = {}
+): Promise<string> {
   const program = await getProgram();
   const keypair = getServerKeypair();
   const campaignIdBytes = getCampaignIdBytes(campaignId);
   const [votingPool] = findVotingPoolPda(campaignId);
+  const [campaign] = findCampaignPda(campaignId);
 
   const tx = await (program.methods as any)
-    .initializeVotingPool(campaignIdBytes, Array.from(eligibilityRoot), zkVerifierProgram)
+    .initializeVotingPool(campaignIdBytes, Array.from(eligibilityRoot), zkVerifierProgram, toPoolOptionsArg(options))
     .accounts({
       votingPool,
+      campaign,
       authority: keypair.publicKey,
       incoLightningProgram: INCO_LIGHTNING_ID,
       systemProgram: SystemProgram.programId,
@@ -129,16 +221,22 @@ export async function initializeVotingPool(campaignId: string, eligibilityRoot:
   return tx;
 }
 
-export async function setEligibilityRoot(campaignId: string, eligibilityRoot: Buffer): Promise<string> {
+export async function setEligibilityRoot(
+  campaignId: string,
+  eligibilityRoot: Buffer,
+  eligibleCount: number
+): Promise<string> {
   const program = await getProgram();
   const keypair = getServerKeypair();
   const campaignIdBytes = getCampaignIdBytes(campaignId);
   const [votingPool] = findVotingPoolPda(campaignId);
+  const [jury] = findJuryPda(campaignId);
 
   const tx = await (program.methods as any)
-    .setEligibilityRoot(campaignIdBytes, Array.from(eligibilityRoot))
+    .setEligibilityRoot(campaignIdBytes, Array.from(eligibilityRoot), new BN(eligibleCount))
     .accounts({
       votingPool,
+      jury,
       authority: keypair.publicKey,
     })
     .rpc();
@@ -163,7 +261,10 @@ export async function castVoteZk(params: {
   const campaignIdBytes = getCampaignIdBytes(campaignId);
   const [votingPool] = findVotingPoolPda(campaignId);
   const [nullifierPda] = findNullifierPda(campaignId, nullifier);
+  const [delegationPda] = findDelegationPda(campaignId, nullifier);
   const zkVerifierProgram = new PublicKey(env.zk.verifierProgramId);
+  // Weight delegated to this voter is applied only when its account is passed.
+  const delegationInfo = await devnetConnection.getAccountInfo(delegationPda);
 
   const tx = await (program.methods as any)
     .castVoteZk(campaignIdBytes, Array.from(nullifier), proof, publicWitness, encryptedVote)
@@ -171,6 +272,48 @@ export async function castVoteZk(params: {
     .accounts({
       nullifier: nullifierPda,
       votingPool,
+      delegation: delegationInfo ? delegationPda : null,
+      relayer: keypair.publicKey,
+      zkVerifierProgram,
+      incoLightningProgram: INCO_LIGHTNING_ID,
+      systemProgram: SystemProgram.programId,
+    })
+    .rpc();
+
+  return tx;
+}
+
+export async function delegateVote(params: {
+  campaignId: string;
+  nullifier: Buffer;
+  delegateNullifier: Buffer;
+  proof: Buffer;
+  publicWitness: Buffer;
+}): Promise<string> {
+  const { campaignId, nullifier, delegateNullifier, proof, publicWitness } = params;
+  if (!env.zk.verifierProgramId) {
+    throw new Error("ZK_VERIFIER_PROGRAM_ID not configured");
+  }
+
+  const program = await getProgram();
+  const keypair = getServerKeypair();
+  const campaignIdBytes = getCampaignIdBytes(campaignId);
+  const [votingPool] = findVotingPoolPda(campaignId);
+  const [nullifierPda] = findNullifierPda(campaignId, nullifier);
+  const [delegation] = findDelegationPda(campaignId, delegateNullifier);
+  const [delegateNullifierAccount] = findNullifierPda(campaignId, delegateNullifier);
+  const [delegatorDelegation] = findDelegationPda(campaignId, nullifier);
+  const zkVerifierProgram = new PublicKey(env.zk.verifierProgramId);
+
+  const tx = await (program.methods as any)
+    .delegateVote(campaignIdBytes, Array.from(nullifier), Array.from(delegateNullifier), proof, publicWitness)
+    .preInstructions([ComputeBudgetProgram.setComputeUnitLimit({ units: 400_000 })])
+    .accounts({
+      nullifier: nullifierPda,
+      delegation,
+      delegateNullifierAccount,
+      delegatorDelegation,
+      votingPool,
       relayer: keypair.publicKey,
       zkVerifierProgram,
       incoLightningProgram: INCO_LIGHTNING_ID,
@@ -181,7 +324,720 @@ export async function castVoteZk(params: {
   return tx;
 }
 
-export async function closeVoting(campaignId: string, allowedAddress: PublicKey): Promise<string> {
+export function findCampaignPda(campaignId: string): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync([Buffer.from("campaign"), Buffer.from(getCampaignIdBytes(campaignId))], PROGRAM_ID);
+}
+
+export async function initializeCampaign(params: {
+  campaignId: string;
+  eligibilityRoot: Buffer;
+  zkVerifierProgram: PublicKey;
+  poolOptions: VotingPoolOptions;
+  winnersDeadline: number;
+  disputeWindowSeconds: number;
+}): Promise<string> {
+  const program = await getProgram();
+  const keypair = getServerKeypair();
+  const campaignIdBytes = getCampaignIdBytes(params.campaignId);
+  const [campaign] = findCampaignPda(params.campaignId);
+  const [votingPool] = findVotingPoolPda(params.campaignId);
+
+  const tx = await (program.methods as any)
+    .initializeCampaign(
+      campaignIdBytes,
+      Array.from(params.eligibilityRoot),
+      params.zkVerifierProgram,
+      toPoolOptionsArg(params.poolOptions),
+      new BN(params.winnersDeadline),
+      new BN(params.disputeWindowSeconds)
+    )
+    .accounts({
+      campaign,
+      votingPool,
+      authority: keypair.publicKey,
+      systemProgram: SystemProgram.programId,
+    })
+    .rpc();
+
+  return tx;
+}
+
+export async function setCampaignEligibility(
+  campaignId: string,
+  eligibilityRoot: Buffer,
+  eligibleCount: number
+): Promise<string> {
+  const program = await getProgram();
+  const keypair = getServerKeypair();
+  const campaignIdBytes = getCampaignIdBytes(campaignId);
+  const [campaign] = findCampaignPda(campaignId);
+
+  const tx = await (program.methods as any)
+    .setCampaignEligibility(campaignIdBytes, Array.from(eligibilityRoot), new BN(eligibleCount))
+    .accounts({
+      campaign,
+      authority: keypair.publicKey,
+    })
+    .rpc();
+
+  return tx;
+}
+
+export async function getCampaignState(campaignId: string): Promise<{
+  disputeOpened: boolean;
+  winnersSelected: boolean;
+} | null> {
+  const program = await getProgram();
+  const [campaign] = findCampaignPda(campaignId);
+  const state = await (program.account as any).campaign.fetchNullable(campaign);
+  if (!state) return null;
+  return { disputeOpened: state.disputeOpened, winnersSelected: state.winnersSelected };
+}
+
+// Commits the winners root once; with an escrow, winners then claim against it via claim_zk.
+export async function commitWinners(campaignId: string, winnersRoot: Buffer, winnerCount: number): Promise<string> {
+  const program = await getProgram();
+  const keypair = getServerKeypair();
+  const campaignIdBytes = getCampaignIdBytes(campaignId);
+  const [campaign] = findCampaignPda(campaignId);
+  const [escrow] = findEscrowPda(campaignId);
+
+  const tx = await (program.methods as any)
+    .commitWinners(campaignIdBytes, Array.from(winnersRoot), winnerCount)
+    .accounts({
+      campaign,
+      escrow,
+      authority: keypair.publicKey,
+    })
+    .rpc();
+
+  return tx;
+}
+
+// Relayed for the recipient like a vote; the server pays for the new pool.
+// Without a proof the server opens it as the campaign authority.
+export async function openDispute(params: {
+  campaignId: string;
+  nullifier?: Buffer;
+  proof?: Buffer;
+  publicWitness?: Buffer;
+}): Promise<string> {
+  if (!env.zk.verifierProgramId) {
+    throw new Error("ZK_VERIFIER_PROGRAM_ID not configured");
+  }
+
+  const program = await getProgram();
+  const keypair = getServerKeypair();
+  const campaignIdBytes = getCampaignIdBytes(params.campaignId);
+  const [campaign] = findCampaignPda(params.campaignId);
+  const [votingPool] = findVotingPoolPda(params.campaignId);
+
+  const tx = await (program.methods as any)
+    .openDispute(
+      campaignIdBytes,
+      Array.from(params.nullifier ?? Buffer.alloc(32)),
+      params.proof ?? Buffer.alloc(0),
+      params.publicWitness ?? Buffer.alloc(0)
+    )
+    .preInstructions([ComputeBudgetProgram.setComputeUnitLimit({ units: 400_000 })])
+    .accounts({
+      campaign,
+      votingPool,
+      opener: keypair.publicKey,
+      zkVerifierProgram: new PublicKey(env.zk.verifierProgramId),
+      incoLightningProgram: INCO_LIGHTNING_ID,
+      systemProgram: SystemProgram.programId,
+    })
+    .rpc();
+
+  return tx;
+}
+
+export function findEvidenceLogPda(campaignId: string): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync([Buffer.from("evidence"), Buffer.from(getCampaignIdBytes(campaignId))], PROGRAM_ID);
+}
+
+// Host records need no proof; recipient records carry a vote-circuit proof over the record.
+export async function submitEvidence(params: {
+  campaignId: string;
+  contentHash: Buffer;
+  encryptedKey: Buffer;
+  submitter: "host" | "recipient";
+  nullifier?: Buffer;
+  proof?: Buffer;
+  publicWitness?: Buffer;
+}): Promise<string> {
+  const program = await getProgram();
+  const keypair = getServerKeypair();
+  const campaignIdBytes = getCampaignIdBytes(params.campaignId);
+  const [votingPool] = findVotingPoolPda(params.campaignId);
+  const [evidenceLog] = findEvidenceLogPda(params.campaignId);
+  const recipient = params.submitter === "recipient";
+  if (recipient && !env.zk.verifierProgramId) {
+    throw new Error("ZK_VERIFIER_PROGRAM_ID not configured");
+  }
+  const nullifier = params.nullifier ?? Buffer.alloc(32);
+  // Recipients spend their nullifier for evidence, one record each.
+  const [evidenceNullifier] = PublicKey.findProgramAddressSync(
+    [Buffer.from("evidence_nullifier"), Buffer.from(campaignIdBytes), nullifier],
+    PROGRAM_ID
+  );
+
+  const tx = await (program.methods as any)
+    .submitEvidence(
+      campaignIdBytes,
+      Array.from(params.contentHash),
+      params.encryptedKey,
+      { [params.submitter]: {} },
+      Array.from(nullifier),
+      params.proof ?? Buffer.alloc(0),
+      params.publicWitness ?? Buffer.alloc(0)
+    )
+    .preInstructions([ComputeBudgetProgram.setComputeUnitLimit({ units: 400_000 })])
+    .accounts({
+      evidenceLog,
+      votingPool,
+      evidenceNullifier: recipient ? evidenceNullifier : null,
+      submitter: keypair.publicKey,
+      zkVerifierProgram: recipient ? new PublicKey(env.zk.verifierProgramId) : null,
+      incoLightningProgram: INCO_LIGHTNING_ID,
+      systemProgram: SystemProgram.programId,
+    })
+    .rpc();
+
+  return tx;
+}
+
+// The reader signs, so a voter's own wallet calls this; the proof commits to its address.
+export async function buildGrantEvidenceAccessInstruction(params: {
+  campaignId: string;
+  reader: PublicKey;
+  nullifier: Buffer;
+  proof: Buffer;
+  publicWitness: Buffer;
+}) {
+  const program = await getProgram();
+  const campaignIdBytes = getCampaignIdBytes(params.campaignId);
+  const [votingPool] = findVotingPoolPda(params.campaignId);
+  const [evidenceLog] = findEvidenceLogPda(params.campaignId);
+  const log = await (program.account as any).evidenceLog.fetch(evidenceLog);
+  const allowances = (log.records as any[]).map((record) => ({
+    pubkey: findAllowancePda(parseHandle(record.encryptedKey), params.reader)[0],
+    isWritable: true,
+    isSigner: false,
+  }));
+
+  return (program.methods as any)
+    .grantEvidenceAccess(campaignIdBytes, Array.from(params.nullifier), params.proof, params.publicWitness)
+    .accounts({
+      evidenceLog,
+      votingPool,
+      reader: params.reader,
+      zkVerifierProgram: new PublicKey(env.zk.verifierProgramId),
+      incoLightningProgram: INCO_LIGHTNING_ID,
+      systemProgram: SystemProgram.programId,
+    })
+    .remainingAccounts(allowances)
+    .instruction();
+}
+
+// `hookAccounts` follow the pool in the hook's account list, as the hook program expects them.
+export async function executeOutcome(
+  campaignId: string,
+  hookProgram: PublicKey,
+  hookAccounts: { pubkey: PublicKey; isWritable: boolean; isSigner: boolean }[] = []
+): Promise<string> {
+  const program = await getProgram();
+  const keypair = getServerKeypair();
+  const campaignIdBytes = getCampaignIdBytes(campaignId);
+  const [votingPool] = findVotingPoolPda(campaignId);
+
+  const tx = await (program.methods as any)
+    .executeOutcome(campaignIdBytes)
+    .accounts({
+      votingPool,
+      hookProgram,
+      requester: keypair.publicKey,
+    })
+    .remainingAccounts(hookAccounts)
+    .rpc();
+
+  return tx;
+}
+
+const TOKEN_PROGRAM_ID = new PublicKey("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+const TOKEN_2022_PROGRAM_ID = new PublicKey("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+const ASSOCIATED_TOKEN_PROGRAM_ID = new PublicKey("ATokenGPvbdGVxr1b2hvZbsWLuSBZrojSyRXWGx4fAd");
+
+export function findEscrowPda(campaignId: string): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync([Buffer.from("escrow"), Buffer.from(getCampaignIdBytes(campaignId))], PROGRAM_ID);
+}
+
+export function findAssociatedTokenAddress(owner: PublicKey, mint: PublicKey, tokenProgram: PublicKey): PublicKey {
+  return PublicKey.findProgramAddressSync(
+    [owner.toBuffer(), tokenProgram.toBuffer(), mint.toBuffer()],
+    ASSOCIATED_TOKEN_PROGRAM_ID
+  )[0];
+}
+
+// Mints are owned by either token program; decimals sit at byte 44 of the base layout.
+export async function getMintInfo(mint: PublicKey): Promise<{ tokenProgram: PublicKey; decimals: number }> {
+  const info = await devnetConnection.getAccountInfo(mint);
+  if (!info || !(info.owner.equals(TOKEN_PROGRAM_ID) || info.owner.equals(TOKEN_2022_PROGRAM_ID))) {
+    throw new Error("Not an SPL Token or Token-2022 mint");
+  }
+  return { tokenProgram: info.owner, decimals: info.data[44] };
+}
+
+// With `mint`, funds are held in the escrow's associated token account for it.
+export async function initializeEscrow(params: {
+  campaignId: string;
+  refundAddress: PublicKey;
+  payoutAmount: number;
+  maxClaims: number;
+  expiresAt: number;
+  winnersDeadline?: number;
+  mint?: PublicKey;
+}): Promise<string> {
+  const program = await getProgram();
+  const keypair = getServerKeypair();
+  const campaignIdBytes = getCampaignIdBytes(params.campaignId);
+  const [escrow] = findEscrowPda(params.campaignId);
+  const args = [
+    campaignIdBytes,
+    params.refundAddress,
+    new BN(params.payoutAmount),
+    params.maxClaims,
+    new BN(params.expiresAt),
+    new BN(params.winnersDeadline ?? 0),
+  ];
+
+  if (params.mint) {
+    const { tokenProgram } = await getMintInfo(params.mint);
+    return (program.methods as any)
+      .initializeTokenEscrow(...args)
+      .accounts({
+        escrow,
+        mint: params.mint,
+        vault: findAssociatedTokenAddress(escrow, params.mint, tokenProgram),
+        authority: keypair.publicKey,
+        tokenProgram,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+  }
+
+  const tx = await (program.methods as any)
+    .initializeEscrow(...args)
+    .accounts({
+      escrow,
+      authority: keypair.publicKey,
+      systemProgram: SystemProgram.programId,
+    })
+    .rpc();
+
+  return tx;
+}
+
+// Token accounts the program expects for an SPL escrow, or nulls for SOL.
+function escrowTokenAccounts(state: any): { vault: PublicKey | null; mint: PublicKey | null; tokenProgram: PublicKey | null } {
+  const mint = new PublicKey(state.mint);
+  if (mint.equals(PublicKey.default)) return { vault: null, mint: null, tokenProgram: null };
+  return { vault: new PublicKey(state.vault), mint, tokenProgram: new PublicKey(state.tokenProgram) };
+}
+
+// Signed by the host's wallet, so the server only builds the instruction.
+export async function buildFundEscrowInstruction(params: { campaignId: string; funder: PublicKey; amount: number }) {
+  const program = await getProgram();
+  const campaignIdBytes = getCampaignIdBytes(params.campaignId);
+  const [escrow] = findEscrowPda(params.campaignId);
+  const tokens = escrowTokenAccounts(await (program.account as any).escrow.fetch(escrow));
+
+  return (program.methods as any)
+    .fundEscrow(campaignIdBytes, new BN(params.amount))
+    .accounts({
+      escrow,
+      funder: params.funder,
+      funderTokenAccount:
+        tokens.mint && tokens.tokenProgram
+          ? findAssociatedTokenAddress(params.funder, tokens.mint, tokens.tokenProgram)
+          : null,
+      ...tokens,
+      systemProgram: SystemProgram.programId,
+    })
+    .instruction();
+}
+
+export async function getEscrowState(campaignId: string): Promise<{
+  refundAddress: PublicKey;
+  payoutAmount: number;
+  maxClaims: number;
+  expiresAt: number;
+  winnersDeadline: number;
+  deposited: number;
+  released: number;
+  balance: number;
+  isClosed: boolean;
+  mint: PublicKey | null;
+  decimals: number;
+  equalDistributionSettled: boolean;
+} | null> {
+  const program = await getProgram();
+  const [escrow] = findEscrowPda(campaignId);
+  try {
+    const state = await (program.account as any).escrow.fetch(escrow);
+    const tokens = escrowTokenAccounts(state);
+    let balance = 0;
+    if (tokens.vault) {
+      balance = Number((await devnetConnection.getTokenAccountBalance(tokens.vault)).value.amount);
+    } else {
+      const info = await devnetConnection.getAccountInfo(escrow);
+      const rent = info ? await devnetConnection.getMinimumBalanceForRentExemption(info.data.length) : 0;
+      balance = info ? Math.max(info.lamports - rent, 0) : 0;
+    }
+    return {
+      refundAddress: new PublicKey(state.refundAddress),
+      payoutAmount: Number(state.payoutAmount),
+      maxClaims: state.maxClaims,
+      expiresAt: Number(state.expiresAt),
+      winnersDeadline: Number(state.winnersDeadline),
+      deposited: Number(state.deposited),
+      released: Number(state.released),
+      balance,
+      isClosed: state.isClosed,
+      mint: tokens.mint,
+      decimals: state.decimals,
+      equalDistributionSettled: state.equalDistributionSettled,
+    };
+  } catch {
+    return null;
+  }
+}
+
+async function findFinalRoundPool(program: Program, campaignId: string): Promise<PublicKey | null> {
+  let [pool] = findVotingPoolPda(campaignId);
+  try {
+    let state = await (program.account as any).votingPool.fetch(pool);
+    while (state.appealPool && !new PublicKey(state.appealPool).equals(PublicKey.default)) {
+      pool = new PublicKey(state.appealPool);
+      state = await (program.account as any).votingPool.fetch(pool);
+    }
+    return pool;
+  } catch {
+    return null;
+  }
+}
+
+// Permissionless: the program checks expiry or the final dispute round's outcome.
+export async function refundEscrow(campaignId: string): Promise<string> {
+  const program = await getProgram();
+  const keypair = getServerKeypair();
+  const campaignIdBytes = getCampaignIdBytes(campaignId);
+  const [escrow] = findEscrowPda(campaignId);
+  const state = await (program.account as any).escrow.fetch(escrow);
+  const refundAddress = new PublicKey(state.refundAddress);
+  const tokens = escrowTokenAccounts(state);
+
+  // SPL refunds go to the refund address's associated token account, which must exist.
+  const tx = await (program.methods as any)
+    .refundEscrow(campaignIdBytes)
+    .accounts({
+      escrow,
+      refundAddress,
+      refundTokenAccount:
+        tokens.mint && tokens.tokenProgram
+          ? findAssociatedTokenAddress(refundAddress, tokens.mint, tokens.tokenProgram)
+          : null,
+      votingPool: await findFinalRoundPool(program, campaignId),
+      requester: keypair.publicKey,
+      ...tokens,
+    })
+    .rpc();
+
+  return tx;
+}
+
+export function findClaimNullifierPda(campaignId: string, nullifier: Uint8Array): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("claim_nullifier"), Buffer.from(getCampaignIdBytes(campaignId)), Buffer.from(nullifier)],
+    PROGRAM_ID
+  );
+}
+
+export async function setClaimRoot(campaignId: string, claimRoot: Buffer): Promise<string> {
+  const program = await getProgram();
+  const keypair = getServerKeypair();
+  const campaignIdBytes = getCampaignIdBytes(campaignId);
+  const [escrow] = findEscrowPda(campaignId);
+
+  const tx = await (program.methods as any)
+    .setClaimRoot(campaignIdBytes, Array.from(claimRoot))
+    .accounts({
+      escrow,
+      authority: keypair.publicKey,
+    })
+    .rpc();
+
+  return tx;
+}
+
+// Relayed by the server, which pays for the nullifier; the proof fixes the recipient.
+export async function claimZk(params: {
+  campaignId: string;
+  recipient: PublicKey;
+  nullifier: Buffer;
+  proof: Buffer;
+  publicWitness: Buffer;
+}): Promise<string> {
+  if (!env.zk.claimVerifierProgramId) {
+    throw new Error("ZK_CLAIM_VERIFIER_PROGRAM_ID not configured");
+  }
+
+  const program = await getProgram();
+  const keypair = getServerKeypair();
+  const campaignIdBytes = getCampaignIdBytes(params.campaignId);
+  const [escrow] = findEscrowPda(params.campaignId);
+  const [claimNullifier] = findClaimNullifierPda(params.campaignId, params.nullifier);
+  const state = await (program.account as any).escrow.fetch(escrow);
+  const tokens = escrowTokenAccounts(state);
+
+  const tx = await (program.methods as any)
+    .claimZk(campaignIdBytes, Array.from(params.nullifier), params.proof, params.publicWitness)
+    .preInstructions([ComputeBudgetProgram.setComputeUnitLimit({ units: 400_000 })])
+    .accounts({
+      escrow,
+      claimNullifier,
+      recipient: params.recipient,
+      recipientTokenAccount:
+        tokens.mint && tokens.tokenProgram
+          ? findAssociatedTokenAddress(params.recipient, tokens.mint, tokens.tokenProgram)
+          : null,
+      payer: keypair.publicKey,
+      zkVerifierProgram: new PublicKey(env.zk.claimVerifierProgramId),
+      ...tokens,
+      systemProgram: SystemProgram.programId,
+    })
+    .rpc();
+
+  return tx;
+}
+
+export function findClaimLogPda(campaignId: string, authority: PublicKey): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("claim_log"), Buffer.from(getCampaignIdBytes(campaignId)), authority.toBuffer()],
+    PROGRAM_ID
+  );
+}
+
+// sha256("claim" || campaign id || identity hash); the identity hash itself never goes on-chain.
+export function getClaimRecordNullifier(campaignId: string, identityHash: Buffer): Buffer {
+  return createHash("sha256")
+    .update(Buffer.from("claim"))
+    .update(Buffer.from(getCampaignIdBytes(campaignId)))
+    .update(identityHash)
+    .digest();
+}
+
+export function findClaimRecordPda(campaignId: string, nullifier: Buffer): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("claim_record"), Buffer.from(getCampaignIdBytes(campaignId)), nullifier],
+    PROGRAM_ID
+  );
+}
+
+// Idempotent so a claim whose payout failed after recording can be retried.
+// Throws ConflictError if the identity was already recorded, so it is never paid twice.
+export async function recordClaim(campaignId: string, identityHash: Buffer, maxClaims: number): Promise<string> {
+  const program = await getProgram();
+  const keypair = getServerKeypair();
+  const campaignIdBytes = getCampaignIdBytes(campaignId);
+  const nullifier = getClaimRecordNullifier(campaignId, identityHash);
+  const [claimLog] = findClaimLogPda(campaignId, keypair.publicKey);
+  const [claimRecord] = findClaimRecordPda(campaignId, nullifier);
+  const [escrow] = findEscrowPda(campaignId);
+
+  if (await devnetConnection.getAccountInfo(claimRecord)) {
+    throw new ConflictError("Claim already recorded");
+  }
+
+  const tx = await (program.methods as any)
+    .recordClaim(campaignIdBytes, Array.from(nullifier), maxClaims)
+    .accounts({
+      claimLog,
+      claimRecord,
+      escrow,
+      authority: keypair.publicKey,
+      systemProgram: SystemProgram.programId,
+    })
+    .rpc();
+
+  return tx;
+}
+
+// Permissionless once the dispute finalized on equal distribution; the dust goes to the refund address.
+export async function settleEqualDistribution(campaignId: string): Promise<string> {
+  const program = await getProgram();
+  const keypair = getServerKeypair();
+  const campaignIdBytes = getCampaignIdBytes(campaignId);
+  const [escrow] = findEscrowPda(campaignId);
+  const [campaign] = findCampaignPda(campaignId);
+  const state = await (program.account as any).escrow.fetch(escrow);
+  const refundAddress = new PublicKey(state.refundAddress);
+  const tokens = escrowTokenAccounts(state);
+  const votingPool = await findFinalRoundPool(program, campaignId);
+  if (!votingPool) {
+    throw new Error("Dispute pool not found");
+  }
+
+  const tx = await (program.methods as any)
+    .settleEqualDistribution(campaignIdBytes)
+    .accounts({
+      escrow,
+      campaign,
+      votingPool,
+      refundAddress,
+      refundTokenAccount:
+        tokens.mint && tokens.tokenProgram
+          ? findAssociatedTokenAddress(refundAddress, tokens.mint, tokens.tokenProgram)
+          : null,
+      requester: keypair.publicKey,
+      ...tokens,
+    })
+    .rpc();
+
+  return tx;
+}
+
+export async function appealVoting(params: {
+  campaignId: string;
+  windowSeconds: number;
+  appellant: Keypair;
+}): Promise<{ signature: string; votingPool: PublicKey; round: number }> {
+  const program = await getProgram();
+  const state = await getVotingPoolState(params.campaignId);
+  if (!state) throw new Error("Voting pool not found");
+
+  // Follow existing appeals so the new round extends the last one.
+  let parentIdBytes = getCampaignIdBytes(params.campaignId);
+  for (let round = 1; round <= state.finalRound; round += 1) {
+    parentIdBytes = getAppealCampaignIdBytes(parentIdBytes, round);
+  }
+  const round = state.finalRound + 1;
+  const roundIdBytes = getAppealCampaignIdBytes(parentIdBytes, round);
+  const [parentPool] = PublicKey.findProgramAddressSync([Buffer.from("voting_pool"), Buffer.from(parentIdBytes)], PROGRAM_ID);
+  const [votingPool] = PublicKey.findProgramAddressSync([Buffer.from("voting_pool"), Buffer.from(roundIdBytes)], PROGRAM_ID);
+
+  const signature = await (program.methods as any)
+    .appealVoting(parentIdBytes, roundIdBytes, new BN(params.windowSeconds))
+    .accounts({
+      parentPool,
+      votingPool,
+      appellant: params.appellant.publicKey,
+      incoLightningProgram: INCO_LIGHTNING_ID,
+      systemProgram: SystemProgram.programId,
+    })
+    .signers([params.appellant])
+    .rpc();
+
+  return { signature, votingPool, round };
+}
+
+export async function checkEarlyDecision(campaignId: string): Promise<string> {
+  const program = await getProgram();
+  const keypair = getServerKeypair();
+  const campaignIdBytes = getCampaignIdBytes(campaignId);
+  const [votingPool] = findVotingPoolPda(campaignId);
+
+  const tx = await (program.methods as any)
+    .checkEarlyDecision(campaignIdBytes)
+    .accounts({
+      votingPool,
+      requester: keypair.publicKey,
+      incoLightningProgram: INCO_LIGHTNING_ID,
+    })
+    .rpc();
+
+  return tx;
+}
+
+export function findJuryPda(campaignId: string): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync([Buffer.from("jury"), Buffer.from(getCampaignIdBytes(campaignId))], PROGRAM_ID);
+}
+
+// The seed stays with the caller until drawJury; only its sha256 goes on-chain.
+export async function commitJurySeed(params: {
+  campaignId: string;
+  seed: Buffer;
+  jurySize: number;
+  juryVerifierProgram: PublicKey;
+}): Promise<string> {
+  const program = await getProgram();
+  const keypair = getServerKeypair();
+  const campaignIdBytes = getCampaignIdBytes(params.campaignId);
+  const [votingPool] = findVotingPoolPda(params.campaignId);
+  const [jury] = findJuryPda(params.campaignId);
+  const seedCommitment = createHash("sha256").update(params.seed).digest();
+
+  const tx = await (program.methods as any)
+    .commitJurySeed(campaignIdBytes, Array.from(seedCommitment), params.jurySize, params.juryVerifierProgram)
+    .accounts({
+      votingPool,
+      jury,
+      authority: keypair.publicKey,
+      systemProgram: SystemProgram.programId,
+    })
+    .rpc();
+
+  return tx;
+}
+
+// Must land within the SlotHashes history (~512 slots) of the commit.
+export async function drawJury(campaignId: string, seed: Buffer): Promise<{ signature: string; indices: number[] }> {
+  const program = await getProgram();
+  const keypair = getServerKeypair();
+  const campaignIdBytes = getCampaignIdBytes(campaignId);
+  const [votingPool] = findVotingPoolPda(campaignId);
+  const [jury] = findJuryPda(campaignId);
+
+  const signature = await (program.methods as any)
+    .drawJury(campaignIdBytes, Array.from(seed))
+    .accounts({
+      votingPool,
+      jury,
+      requester: keypair.publicKey,
+      slotHashes: SYSVAR_SLOT_HASHES_PUBKEY,
+    })
+    .rpc();
+
+  const account = await (program.account as any).jury.fetch(jury);
+  return { signature, indices: account.indices as number[] };
+}
+
+// Reopens ballots without a jury once the committed draw has fallen out of the SlotHashes history.
+export async function abandonJurySeed(campaignId: string): Promise<string> {
+  const program = await getProgram();
+  const keypair = getServerKeypair();
+  const campaignIdBytes = getCampaignIdBytes(campaignId);
+  const [votingPool] = findVotingPoolPda(campaignId);
+  const [jury] = findJuryPda(campaignId);
+
+  return (program.methods as any)
+    .abandonJurySeed(campaignIdBytes)
+    .accounts({
+      votingPool,
+      jury,
+      requester: keypair.publicKey,
+      slotHashes: SYSVAR_SLOT_HASHES_PUBKEY,
+    })
+    .rpc();
+}
+
+export async function closeVoting(
+  campaignId: string,
+  allowedAddress: PublicKey,
+  extraDecryptors: PublicKey[] = []
+): Promise<string> {
   const program = await getProgram();
   const keypair = getServerKeypair();
   const campaignIdBytes = getCampaignIdBytes(campaignId);
@@ -190,8 +1046,37 @@ export async function closeVoting(campaignId: string, allowedAddress: PublicKey)
   const state = await getVotingPoolState(campaignId);
   if (!state) throw new Error("Voting pool not found");
 
-  const [allowanceRefund] = findAllowancePda(state.refundHostVotesHandle, allowedAddress);
-  const [allowanceEqual] = findAllowancePda(state.equalDistributionVotesHandle, allowedAddress);
+  const approval = state.optionTallyHandles.length > 0;
+  const allowanceRefund = approval ? null : findAllowancePda(state.refundHostVotesHandle, allowedAddress)[0];
+  const allowanceEqual = approval ? null : findAllowancePda(state.equalDistributionVotesHandle, allowedAddress)[0];
+  const allowanceTurnout = state.encryptTurnout
+    ? findAllowancePda(state.encryptedTotalVotesHandle, allowedAddress)[0]
+    : null;
+
+  // Option and later-question tallies have no named accounts; the primary decryptor's allowances go first.
+  const extraTallyHandles = approval ? state.optionTallyHandles : state.questionTallyHandles;
+  const optionAllowances = extraTallyHandles.map((handle) => ({
+    pubkey: findAllowancePda(handle, allowedAddress)[0],
+    isSigner: false,
+    isWritable: true,
+  }));
+
+  // Each extra decryptor is followed by its allowance for every revealed handle, in order.
+  const revealedHandles = approval
+    ? [...state.optionTallyHandles]
+    : [state.refundHostVotesHandle, state.equalDistributionVotesHandle, ...state.questionTallyHandles];
+  if (state.encryptTurnout) revealedHandles.push(state.encryptedTotalVotesHandle);
+  const remainingAccounts = [
+    ...optionAllowances,
+    ...extraDecryptors.flatMap((decryptor) => [
+      { pubkey: decryptor, isSigner: false, isWritable: false },
+      ...revealedHandles.map((handle) => ({
+        pubkey: findAllowancePda(handle, decryptor)[0],
+        isSigner: false,
+        isWritable: true,
+      })),
+    ]),
+  ];
 
   const tx = await (program.methods as any)
     .closeVoting(campaignIdBytes, allowedAddress)
@@ -201,9 +1086,11 @@ export async function closeVoting(campaignId: string, allowedAddress: PublicKey)
       allowedAddress,
       allowanceRefund,
       allowanceEqual,
+      allowanceTurnout,
       incoLightningProgram: INCO_LIGHTNING_ID,
       systemProgram: SystemProgram.programId,
     })
+    .remainingAccounts(remainingAccounts)
     .rpc();
 
   return tx;
@@ -348,11 +1235,20 @@ export async function getAnalyticsState(campaignId: string): Promise<{
 export async function getVotingPoolState(campaignId: string): Promise<{
   totalVotes: number;
   isActive: boolean;
+  hasVotes: boolean;
+  encryptTurnout: boolean;
   refundHostVotesHandle: bigint;
   equalDistributionVotesHandle: bigint;
+  encryptedTotalVotesHandle: bigint;
+  optionTallyHandles: bigint[];
+  questionTallyHandles: bigint[];
+  finalRound: number;
   authority: PublicKey;
   eligibilityRoot: Buffer;
   zkVerifierProgram: PublicKey;
+  eligibleCount: number;
+  outcomePolicy: OutcomePolicy;
+  outcome: PolicyOutcome | "pending";
 } | null> {
   const program = await getProgram();
   const [votingPool] = findVotingPoolPda(campaignId);
@@ -362,17 +1258,39 @@ export async function getVotingPoolState(campaignId: string): Promise<{
     return {
       totalVotes: state.totalVotes.toNumber(),
       isActive: state.isActive,
+      hasVotes: state.hasVotes,
+      encryptTurnout: state.encryptTurnout,
       refundHostVotesHandle: parseHandle(state.refundHostVotes),
       equalDistributionVotesHandle: parseHandle(state.equalDistributionVotes),
+      encryptedTotalVotesHandle: parseHandle(state.encryptedTotalVotes),
+      optionTallyHandles: state.optionTallies.map(parseHandle),
+      questionTallyHandles: state.questionTallies.map(parseHandle),
+      finalRound: await getFinalRound(program, state),
       authority: state.authority,
       eligibilityRoot: parseBytes32(state.eligibilityRoot),
       zkVerifierProgram: state.zkVerifierProgram,
+      eligibleCount: state.eligibleCount.toNumber(),
+      outcomePolicy: {
+        tieBreak: Object.keys(state.outcomePolicy.tieBreak)[0] as PolicyOutcome,
+        minMargin: state.outcomePolicy.minMargin.toNumber(),
+        quorumBps: state.outcomePolicy.quorumBps,
+        quorumDefault: Object.keys(state.outcomePolicy.quorumDefault)[0] as PolicyOutcome,
+      },
+      outcome: Object.keys(state.outcome)[0] as PolicyOutcome | "pending",
     };
   } catch {
     return null;
   }
 }
 
+async function getFinalRound(program: Program, state: any): Promise<number> {
+  let current = state;
+  while (current.appealPool && !new PublicKey(current.appealPool).equals(PublicKey.default)) {
+    current = await (program.account as any).votingPool.fetch(current.appealPool);
+  }
+  return current.round;
+}
+
 export async function decryptVoteTotals(campaignId: string): Promise<{ refundHost: number; equalDistribution: number } | null> {
   const state = await getVotingPoolState(campaignId);
   if (!state) return null;
@@ -134,3 +134,40 @@ export async function getMerkleProof(
     index: targetIndex,
   };
 }
+
+// Must match JURY_DEPTH in the program and the jury_vote_eligibility circuit.
+export const JURY_DEPTH = 6;
+
+// Jury leaves are poseidon(index + 1) over the drawn indices, in ascending order.
+export async function getJuryMerkleProof(
+  juryIndices: number[],
+  leafIndex: number
+): Promise<{
+  root: Buffer;
+  siblings: Buffer[];
+  pathBits: number[];
+}> {
+  const position = juryIndices.indexOf(leafIndex);
+  if (position === -1) {
+    throw new Error("Leaf is not on the jury");
+  }
+
+  const leaves = await Promise.all(juryIndices.map((index) => poseidonHashFields([new BN(index + 1)])));
+  const layers = await buildLayers(leaves, JURY_DEPTH);
+  let index = position;
+  const siblings: Buffer[] = [];
+  const pathBits: number[] = [];
+
+  for (let level = 0; level < JURY_DEPTH; level += 1) {
+    const isRight = index % 2 === 1;
+    siblings.push(layers[level][isRight ? index - 1 : index + 1]);
+    pathBits.push(isRight ? 1 : 0);
+    index = Math.floor(index / 2);
+  }
+
+  return {
+    root: layers[layers.length - 1][0],
+    siblings,
+    pathBits,
+  };
+}
@@ -1,6 +1,7 @@
 import { promises as fs } from "fs";
 import path from "path";
 import { spawn } from "child_process";
+import { createHash } from "crypto";
 import BN from "bn.js";
 import { WasmFactory } from "@lightprotocol/hasher.rs";
 import { getMerkleProof } from "./merkle";
@@ -8,6 +9,21 @@ import { getMerkleProof } from "./merkle";
 const CHUNK_SIZE = 16;
 const SECRET_LENGTH = 32;
 const CIPHERTEXT_FIELDS = 8;
+const CIPHERTEXT_LENGTH = 114;
+const BALLOT_META_LENGTH = 14;
+const APPROVAL_OPTIONS_OFFSET = 8;
+const BALLOT_KIND_DELEGATION = 1;
+const BALLOT_KIND_DISPUTE = 2;
+const BALLOT_KIND_EVIDENCE = 3;
+const BALLOT_KIND_EVIDENCE_ACCESS = 4;
+// "claim" as a field; separates claim nullifiers from vote nullifiers.
+const CLAIM_NULLIFIER_DOMAIN = Buffer.from("claim");
+// Ballot kinds whose proofs reveal a nullifier in their own domain rather than the vote nullifier.
+const NULLIFIER_DOMAINS: Record<number, Buffer> = {
+  [BALLOT_KIND_DISPUTE]: Buffer.from("dispute"),
+  [BALLOT_KIND_EVIDENCE]: Buffer.from("evidence"),
+  [BALLOT_KIND_EVIDENCE_ACCESS]: Buffer.from("evidence_access"),
+};
 
 let hasherPromise: ReturnType<typeof WasmFactory.getInstance> | null = null;
 let proofQueue: Promise<void> = Promise.resolve();
@@ -78,10 +94,10 @@ async function enqueueProof<T>(task: () => Promise<T>): Promise<T> {
   return next;
 }
 
-async function ensureArtifacts(noirDir: string, targetDir: string): Promise<void> {
-  const compiled = path.join(targetDir, "vote_eligibility.json");
-  const ccs = path.join(targetDir, "vote_eligibility.ccs");
-  const pk = path.join(targetDir, "vote_eligibility.pk");
+async function ensureArtifacts(noirDir: string, targetDir: string, circuit = "vote_eligibility"): Promise<void> {
+  const compiled = path.join(targetDir, `${circuit}.json`);
+  const ccs = path.join(targetDir, `${circuit}.ccs`);
+  const pk = path.join(targetDir, `${circuit}.pk`);
 
   try {
     await fs.access(compiled);
@@ -98,14 +114,17 @@ async function ensureArtifacts(noirDir: string, targetDir: string): Promise<void
   }
 }
 
-async function generateProof(noirDir: string): Promise<{ proof: string; publicWitness: string }> {
+async function generateProof(
+  noirDir: string,
+  circuit = "vote_eligibility"
+): Promise<{ proof: string; publicWitness: string }> {
   const targetDir = path.join(noirDir, "target");
-  const proofPath = path.join(targetDir, "vote_eligibility.proof");
-  const witnessPath = path.join(targetDir, "vote_eligibility.gz");
-  const compiled = path.join(targetDir, "vote_eligibility.json");
-  const ccs = path.join(targetDir, "vote_eligibility.ccs");
-  const pk = path.join(targetDir, "vote_eligibility.pk");
-  const pw = path.join(targetDir, "vote_eligibility.pw");
+  const proofPath = path.join(targetDir, `${circuit}.proof`);
+  const witnessPath = path.join(targetDir, `${circuit}.gz`);
+  const compiled = path.join(targetDir, `${circuit}.json`);
+  const ccs = path.join(targetDir, `${circuit}.ccs`);
+  const pk = path.join(targetDir, `${circuit}.pk`);
+  const pw = path.join(targetDir, `${circuit}.pw`);
 
   await runCommand("nargo", ["execute"], noirDir);
   await runCommand("sunspot", ["prove", compiled, witnessPath, ccs, pk], noirDir);
@@ -123,6 +142,8 @@ async function writeProverToml(
     pathBits: number[];
     secret: Buffer;
     ciphertext: Buffer;
+    ballotMeta: Buffer;
+    approvals: number;
     merkleRoot: string;
     nullifier: string;
     commitment: string;
@@ -141,6 +162,10 @@ secret = ${bytesToToml(params.secret)}
 
 ciphertext = ${bytesToToml(params.ciphertext)}
 
+ballot_meta = ${bytesToToml(params.ballotMeta)}
+
+approvals = ${params.approvals}
+
 merkle_root = "${params.merkleRoot}"
 nullifier = "${params.nullifier}"
 commitment = "${params.commitment}"
@@ -153,6 +178,11 @@ export async function buildVoteProof(params: {
   identityHash: string;
   ciphertextHex: string;
   merkleDepth: number;
+  sequence?: number;
+  ballotKind?: number;
+  // Approval ballots pass the mask they encrypted and the pool's option count.
+  approvals?: number;
+  approvalOptions?: number;
 }): Promise<{ proof: string; publicWitness: string; nullifier: string }> {
   const projectRoot = resolveProjectRoot();
   const noirDir = path.join(projectRoot, "zk", "noir", "vote_eligibility");
@@ -170,10 +200,17 @@ export async function buildVoteProof(params: {
   const proofData = await getMerkleProof(params.leafHexes, params.identityHash, params.merkleDepth);
   const secret = identity;
   const secretChunks = chunkBytes(secret, CHUNK_SIZE, 2);
-  // Nullifier is Poseidon(secret chunks); commitment is Poseidon(ciphertext chunks).
-  const nullifierBuf = await poseidonHash(secretChunks);
-  const ciphertextChunks = chunkBytes(ciphertext, CHUNK_SIZE, CIPHERTEXT_FIELDS);
-  const commitmentBuf = await poseidonHash(ciphertextChunks);
+  // Ballot metadata carries the revote sequence (zero for a first ballot), an approval pool's
+  // option count, and the ballot kind in its last byte.
+  const ballotMeta = Buffer.alloc(BALLOT_META_LENGTH);
+  ballotMeta.writeBigUInt64BE(BigInt(params.sequence ?? 0), 0);
+  ballotMeta[APPROVAL_OPTIONS_OFFSET] = params.approvalOptions ?? 0;
+  ballotMeta[BALLOT_META_LENGTH - 1] = params.ballotKind ?? 0;
+  // Nullifier is Poseidon(secret chunks[, domain]); commitment is Poseidon((ciphertext || metadata) chunks).
+  const domain = NULLIFIER_DOMAINS[params.ballotKind ?? 0];
+  const nullifierBuf = await poseidonHash(domain ? [...secretChunks, domain] : secretChunks);
+  const ballotChunks = chunkBytes(Buffer.concat([ciphertext, ballotMeta]), CHUNK_SIZE, CIPHERTEXT_FIELDS);
+  const commitmentBuf = await poseidonHash(ballotChunks);
   const merkleRootDec = new BN(proofData.root).toString(10);
   const nullifierDec = new BN(nullifierBuf).toString(10);
   const commitmentDec = new BN(commitmentBuf).toString(10);
@@ -187,6 +224,8 @@ export async function buildVoteProof(params: {
       pathBits: proofData.pathBits,
       secret,
       ciphertext,
+      ballotMeta,
+      approvals: params.approvals ?? 0,
       merkleRoot: merkleRootDec,
       nullifier: nullifierDec,
       commitment: commitmentDec,
@@ -196,3 +235,127 @@ export async function buildVoteProof(params: {
     return { proof, publicWitness, nullifier: nullifierBuf.toString("hex") };
   });
 }
+
+export async function buildDelegationProof(params: {
+  leafHexes: string[];
+  identityHash: string;
+  delegateNullifierHex: string;
+  merkleDepth: number;
+}): Promise<{ proof: string; publicWitness: string; nullifier: string }> {
+  const delegate = Buffer.from(params.delegateNullifierHex, "hex");
+  if (delegate.length !== 32) {
+    throw new Error("delegate nullifier must be 32 bytes");
+  }
+  // A delegation commits to the delegate's nullifier in the ciphertext slot.
+  const payload = Buffer.alloc(CIPHERTEXT_LENGTH);
+  delegate.copy(payload, 0);
+  return buildVoteProof({
+    leafHexes: params.leafHexes,
+    identityHash: params.identityHash,
+    ciphertextHex: payload.toString("hex"),
+    merkleDepth: params.merkleDepth,
+    ballotKind: BALLOT_KIND_DELEGATION,
+  });
+}
+
+export async function buildDisputeOpeningProof(params: {
+  leafHexes: string[];
+  identityHash: string;
+  campaignIdBytes: number[];
+  merkleDepth: number;
+}): Promise<{ proof: string; publicWitness: string; nullifier: string }> {
+  // Opening a dispute commits to the campaign id; its nullifier is in the dispute domain and isn't spent.
+  const payload = Buffer.alloc(CIPHERTEXT_LENGTH);
+  Buffer.from(params.campaignIdBytes).copy(payload, 0);
+  return buildVoteProof({
+    leafHexes: params.leafHexes,
+    identityHash: params.identityHash,
+    ciphertextHex: payload.toString("hex"),
+    merkleDepth: params.merkleDepth,
+    ballotKind: BALLOT_KIND_DISPUTE,
+  });
+}
+
+export async function buildEvidenceProof(params: {
+  leafHexes: string[];
+  identityHash: string;
+  contentHash: Buffer;
+  encryptedKey: Buffer;
+  merkleDepth: number;
+}): Promise<{ proof: string; publicWitness: string; nullifier: string }> {
+  // Recipient evidence commits to sha256(contentHash || encryptedKey).
+  const payload = Buffer.alloc(CIPHERTEXT_LENGTH);
+  createHash("sha256").update(params.contentHash).update(params.encryptedKey).digest().copy(payload, 0);
+  return buildVoteProof({
+    leafHexes: params.leafHexes,
+    identityHash: params.identityHash,
+    ciphertextHex: payload.toString("hex"),
+    merkleDepth: params.merkleDepth,
+    ballotKind: BALLOT_KIND_EVIDENCE,
+  });
+}
+
+export async function buildEvidenceAccessProof(params: {
+  leafHexes: string[];
+  identityHash: string;
+  reader: Buffer;
+  merkleDepth: number;
+}): Promise<{ proof: string; publicWitness: string; nullifier: string }> {
+  const payload = Buffer.alloc(CIPHERTEXT_LENGTH);
+  params.reader.copy(payload, 0);
+  return buildVoteProof({
+    leafHexes: params.leafHexes,
+    identityHash: params.identityHash,
+    ciphertextHex: payload.toString("hex"),
+    merkleDepth: params.merkleDepth,
+    ballotKind: BALLOT_KIND_EVIDENCE_ACCESS,
+  });
+}
+
+// Claims use the claim_eligibility circuit, whose proof binds the recipient wallet.
+export async function buildClaimProof(params: {
+  leafHexes: string[];
+  identityHash: string;
+  recipient: Buffer;
+  merkleDepth: number;
+}): Promise<{ proof: string; publicWitness: string; nullifier: string }> {
+  const projectRoot = resolveProjectRoot();
+  const noirDir = path.join(projectRoot, "zk", "noir", "claim_eligibility");
+  const targetDir = path.join(noirDir, "target");
+
+  const identity = Buffer.from(params.identityHash, "hex");
+  if (identity.length !== SECRET_LENGTH) {
+    throw new Error("identityHash must be 32 bytes");
+  }
+  if (params.recipient.length !== 32) {
+    throw new Error("recipient must be 32 bytes");
+  }
+
+  const proofData = await getMerkleProof(params.leafHexes, params.identityHash, params.merkleDepth);
+  const nullifierBuf = await poseidonHash([...chunkBytes(identity, CHUNK_SIZE, 2), CLAIM_NULLIFIER_DOMAIN]);
+  const recipientBuf = await poseidonHash(chunkBytes(params.recipient, CHUNK_SIZE, 2));
+
+  await ensureArtifacts(noirDir, targetDir, "claim_eligibility");
+
+  return enqueueProof(async () => {
+    const siblingsToml = proofData.siblings.map(bytesToToml).join(",\n  ");
+    const contents = `leaf = ${bytesToToml(proofData.leaf)}
+
+siblings = [
+  ${siblingsToml}
+]
+
+path_bits = [${proofData.pathBits.join(", ")}]
+
+wallet = ${bytesToToml(params.recipient)}
+
+merkle_root = "${new BN(proofData.root).toString(10)}"
+nullifier = "${new BN(nullifierBuf).toString(10)}"
+recipient = "${new BN(recipientBuf).toString(10)}"
+`;
+    await fs.writeFile(path.join(noirDir, "Prover.toml"), contents);
+
+    const { proof, publicWitness } = await generateProof(noirDir, "claim_eligibility");
+    return { proof, publicWitness, nullifier: nullifierBuf.toString("hex") };
+  });
+}
@@ -35,6 +35,7 @@ export async function handleCreateCampaign(req: Request, res: Response, next: Ne
       recipients,
       requireCompliance,
       refundAddress,
+      payoutMint,
       theme,
     } = req.body;
 
@@ -51,6 +52,10 @@ export async function handleCreateCampaign(req: Request, res: Response, next: Ne
     if (refundAddress && !isValidPublicKey(refundAddress)) {
       throw new BadRequestError("Invalid refundAddress");
     }
+    // Token payouts are only held in the on-chain escrow vault, which needs a refund address.
+    if (payoutMint && (type !== "escrow" || !refundAddress || !isValidPublicKey(payoutMint))) {
+      throw new BadRequestError("Invalid payoutMint");
+    }
     const validatedTheme = validateTheme(theme);
 
     const result = await campaignService.createCampaign(req.user!.userId, req.user!.orgSlug, {
@@ -65,6 +70,7 @@ export async function handleCreateCampaign(req: Request, res: Response, next: Ne
       recipients,
       requireCompliance,
       refundAddress,
+      payoutMint,
       theme: validatedTheme,
     });
 
@@ -37,6 +37,9 @@ export interface CampaignDoc {
   disputeStartedAt?: number;
   disputeEndsAt?: number;
   refundAddress?: string;
+  // SPL Token or Token-2022 mint escrow payouts are made in; SOL when unset.
+  payoutMint?: string;
+  payoutDecimals?: number;
   disputeOutcome?: "refund-host" | "equal-distribution" | "tie";
   voteResults?: {
     refundHost: number;
@@ -59,6 +62,7 @@ export interface CreateCampaignInput {
   winnersDeadline?: number;
   recipients: string[];
   refundAddress?: string;
+  payoutMint?: string;
   theme?: CampaignTheme;
   requireCompliance?: boolean;
 }
@@ -101,6 +105,8 @@ export interface CampaignPublic {
   type: CampaignType;
   authMethod: AuthMethod;
   payoutAmount: number;
+  payoutMint?: string;
+  payoutDecimals?: number;
   maxClaims: number;
   claimCount: number;
   expiresAt: number;
@@ -35,6 +35,8 @@ async function getAutoDepositBufferLamports(): Promise<number> {
 
 export async function getTotalRequiredLamports(campaign: CampaignDoc): Promise<number> {
   const baseRequired = campaign.payoutAmount * campaign.maxClaims;
+  // Token payouts are funded straight into the vault in base units.
+  if (campaign.payoutMint) return baseRequired;
   try {
     const estimate = await getWithdrawEstimate(campaign.payoutAmount);
     return estimate.requestedLamports * campaign.maxClaims;
@@ -51,6 +53,14 @@ export async function createCampaign(
   const id = generateId();
   const identityHashes = input.recipients.map((r: string) => hashIdentity(input.authMethod, r).toString("hex"));
   const eligibilityRoot = (await buildMerkleRoot(identityHashes, env.zk.merkleDepth)).toString("hex");
+  let payoutDecimals: number | undefined;
+  if (input.payoutMint) {
+    try {
+      payoutDecimals = (await inco.getMintInfo(new PublicKey(input.payoutMint))).decimals;
+    } catch {
+      throw new BadRequestError("Invalid payoutMint");
+    }
+  }
 
   const doc: CampaignDoc = {
     id,
@@ -74,6 +84,8 @@ export async function createCampaign(
     selectedWinners: input.type === "escrow" ? [] : undefined,
     status: "pending-funding",
     refundAddress: input.refundAddress,
+    payoutMint: input.payoutMint,
+    payoutDecimals,
     createdAt: Date.now(),
   };
 
@@ -92,6 +104,39 @@ export async function createCampaign(
     console.error("Failed to initialize on-chain analytics:", error);
   }
 
+  // Escrow terms go on-chain so recipients can open a dispute if the server doesn't.
+  if (doc.type === "escrow" && doc.winnersDeadline && env.zk.verifierProgramId) {
+    try {
+      await inco.initializeCampaign({
+        campaignId: id,
+        eligibilityRoot: Buffer.from(eligibilityRoot, "hex"),
+        zkVerifierProgram: new PublicKey(env.zk.verifierProgramId),
+        poolOptions: { eligibleCount: identityHashes.length },
+        winnersDeadline: doc.winnersDeadline,
+        disputeWindowSeconds: env.voting.disputeWindowSeconds,
+      });
+    } catch (error) {
+      console.error("Failed to initialize on-chain campaign:", error);
+    }
+  }
+
+  // The vault's release rules, not the server key, decide where escrowed funds go.
+  if (doc.type === "escrow" && doc.refundAddress) {
+    try {
+      await inco.initializeEscrow({
+        campaignId: id,
+        refundAddress: new PublicKey(doc.refundAddress),
+        payoutAmount: doc.payoutAmount,
+        maxClaims: doc.maxClaims,
+        expiresAt: doc.expiresAt,
+        winnersDeadline: doc.winnersDeadline,
+        mint: doc.payoutMint ? new PublicKey(doc.payoutMint) : undefined,
+      });
+    } catch (error) {
+      console.error("Failed to initialize escrow vault:", error);
+    }
+  }
+
   const totalRequired = await getTotalRequiredLamports(doc);
   return { campaign: toPublic(doc), fundingAddress, identityHashes, totalRequired };
 }
@@ -428,10 +473,16 @@ export async function addRecipients(id: string, userId: string, recipients: stri
 
     if (doc.status === "dispute") {
       try {
-        await inco.setEligibilityRoot(id, Buffer.from(eligibilityRoot, "hex"));
+        await inco.setEligibilityRoot(id, Buffer.from(eligibilityRoot, "hex"), updatedHashes.length);
       } catch (error) {
         console.error("Failed to update on-chain eligibility root:", error);
       }
+    } else if (doc.type === "escrow" && doc.winnersDeadline && Date.now() / 1000 <= doc.winnersDeadline) {
+      try {
+        await inco.setCampaignEligibility(id, Buffer.from(eligibilityRoot, "hex"), updatedHashes.length);
+      } catch (error) {
+        console.error("Failed to update on-chain campaign eligibility:", error);
+      }
     }
   }
 
@@ -452,6 +503,15 @@ export async function closeCampaign(
   if (doc.status === "closed" && !allowEarlyRefund) throw new BadRequestError("Campaign already closed");
   if (!allowEarlyRefund && doc.expiresAt > Date.now() / 1000) throw new BadRequestError("Campaign not yet expired");
 
+  if (doc.type === "escrow" && doc.refundAddress) {
+    try {
+      const escrow = await inco.getEscrowState(id);
+      if (escrow && !escrow.isClosed && escrow.balance > 0) await inco.refundEscrow(id);
+    } catch (error) {
+      console.error("Failed to refund escrow vault:", error);
+    }
+  }
+
   const balance = await getCampaignPrivateBalance(id);
 
   if (balance > 0) {
@@ -482,6 +542,14 @@ export async function selectWinners(id: string, userId: string, winners: string[
 
   await col.updateOne({ id }, { $set: { selectedWinners: winnerHashes, status: "winners-announced" } });
 
+  try {
+    // The committed root is final, so the winners can't be rewritten after this.
+    const winnersRoot = await buildMerkleRoot(winnerHashes, env.zk.merkleDepth);
+    await inco.commitWinners(id, winnersRoot, winnerHashes.length);
+  } catch (error) {
+    console.error("Failed to commit winners on-chain:", error);
+  }
+
   return { winnersCount: winnerHashes.length };
 }
 
@@ -535,13 +603,21 @@ export async function checkAndTriggerDispute(id: string): Promise<void> {
   const disputeEndsAt = now + env.voting.disputeWindowSeconds;
   const zkVerifierProgram = new PublicKey(env.zk.verifierProgramId);
 
-  try {
-    await inco.initializeVotingPool(id, eligibilityRoot, zkVerifierProgram);
-  } catch (error: any) {
-    if (!error.message?.includes("already in use")) {
-      throw error;
+  // Campaigns with on-chain terms only get a dispute pool through open_dispute.
+  const campaignState = await inco.getCampaignState(id);
+  if (campaignState) {
+    if (!campaignState.disputeOpened) await inco.openDispute({ campaignId: id });
+  } else {
+    try {
+      await inco.initializeVotingPool(id, eligibilityRoot, zkVerifierProgram, {
+        eligibleCount: doc.eligibleHashes.length,
+      });
+    } catch (error: any) {
+      if (!error.message?.includes("already in use")) {
+        throw error;
+      }
+      await inco.setEligibilityRoot(id, eligibilityRoot, doc.eligibleHashes.length);
     }
-    await inco.setEligibilityRoot(id, eligibilityRoot);
   }
 
   await col.updateOne(
@@ -567,6 +643,8 @@ function toPublic(doc: CampaignDoc): CampaignPublic {
     type: doc.type,
     authMethod: doc.authMethod,
     payoutAmount: doc.payoutAmount,
+    payoutMint: doc.payoutMint,
+    payoutDecimals: doc.payoutDecimals,
     maxClaims: doc.maxClaims,
     claimCount: doc.claimCount,
     expiresAt: doc.expiresAt,
@@ -1,5 +1,9 @@
+import { PublicKey } from "@solana/web3.js";
+import { env } from "@/config";
 import { withdraw, getWithdrawEstimate } from "@/lib/privacy-cash";
+import { buildClaimProof } from "@/lib/zk";
 import { BadRequestError, ConflictError } from "@/shared";
+import * as inco from "@/lib/inco";
 import { trackEvent } from "@/modules/analytics";
 import { claimsCollection } from "./claim.model";
 import {
@@ -96,6 +100,31 @@ export async function processClaim(campaignId: string, token: string, walletAddr
 
     await trackEvent({ campaignId, eventType: "claim-attempt", identityHash });
 
+    // Winners of a funded escrow vault are paid by the program against the committed winners root.
+    const escrow = campaign.type === "escrow" ? await inco.getEscrowState(campaignId) : null;
+    if (escrow && !escrow.isClosed && escrow.balance >= escrow.payoutAmount) {
+      const claimProof = await buildClaimProof({
+        leafHexes: campaign.selectedWinners || [],
+        identityHash,
+        recipient: new PublicKey(walletAddress).toBuffer(),
+        merkleDepth: env.zk.merkleDepth,
+      });
+      const signature = await inco.claimZk({
+        campaignId,
+        recipient: new PublicKey(walletAddress),
+        nullifier: Buffer.from(claimProof.nullifier, "hex"),
+        proof: Buffer.from(claimProof.proof, "base64"),
+        publicWitness: Buffer.from(claimProof.publicWitness, "base64"),
+      });
+
+      await claimsCollection().updateOne({ campaignId, identityHash }, { $set: { signature, compliance } });
+      await incrementClaimCount(campaignId);
+      await consumeVerificationToken(token);
+      await trackEvent({ campaignId, eventType: "claim-success", identityHash });
+
+      return { signature, amount: escrow.payoutAmount, compliance };
+    }
+
     const keys = await getCampaignWalletKeys(campaignId);
     const estimate = await getWithdrawEstimate(campaign.payoutAmount);
     if (estimate.netLamports < campaign.payoutAmount) {
@@ -106,6 +135,10 @@ export async function processClaim(campaignId: string, token: string, walletAddr
       throw new BadRequestError("Campaign balance too low to cover relayer fees");
     }
 
+    // The on-chain log enforces maxClaims and keeps an auditable record of every payout.
+    // It throws ConflictError for an identity already recorded, before anything is paid.
+    await inco.recordClaim(campaignId, Buffer.from(identityHash, "hex"), campaign.maxClaims);
+
     const result = await withdraw(keys, estimate.requestedLamports, walletAddress);
 
     await claimsCollection().updateOne(
@@ -2,7 +2,7 @@ import { env } from "@/config";
 import { logger } from "@/shared";
 import { campaignsCollection } from "@/modules/campaign";
 import { checkAndTriggerDispute } from "@/modules/campaign";
-import { resolveDisputeAsServer } from "./voting.service";
+import { APPEAL_WINDOW_SECONDS, resolveDisputeAsServer, settleEscrowVault } from "./voting.service";
 
 let scheduler: NodeJS.Timeout | null = null;
 
@@ -38,6 +38,24 @@ async function runDisputeCycle(): Promise<void> {
       logger.error("Failed to resolve dispute", { campaignId: campaign.id, error: String(error) });
     }
   }
+
+  // Vaults stay locked through the appeal window, so settle the ones it has freed.
+  const resolved = await campaignsCollection()
+    .find({
+      type: "escrow",
+      disputeOutcome: { $exists: true },
+      votingClosedAt: { $lte: (now - APPEAL_WINDOW_SECONDS) * 1000 },
+      expiresAt: { $gt: now },
+    })
+    .toArray();
+
+  for (const campaign of resolved) {
+    try {
+      await settleEscrowVault(campaign.id, campaign.disputeOutcome!);
+    } catch (error) {
+      logger.error("Failed to settle escrow vault", { campaignId: campaign.id, error: String(error) });
+    }
+  }
 }
 
 export function startDisputeScheduler(): void {
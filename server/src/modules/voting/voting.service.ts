@@ -25,10 +25,42 @@ async function ensureEligibilityRoot(campaign: CampaignDoc): Promise<Buffer> {
   return root;
 }
 
-function resolveOutcome(refundHost: number, equalDistribution: number): "refund-host" | "equal-distribution" | "tie" {
-  if (refundHost > equalDistribution) return "refund-host";
-  if (equalDistribution > refundHost) return "equal-distribution";
-  return "tie";
+type DisputeOutcome = "refund-host" | "equal-distribution" | "tie";
+
+const POLICY_OUTCOMES: Record<inco.PolicyOutcome, DisputeOutcome> = {
+  refundHost: "refund-host",
+  equalDistribution: "equal-distribution",
+  tie: "tie",
+};
+
+// Same rules as the program's compute_encrypted_outcome, so both agree on the pool's policy.
+function resolveOutcome(
+  refundHost: number,
+  equalDistribution: number,
+  policy: inco.OutcomePolicy,
+  eligibleCount: number
+): DisputeOutcome {
+  if (policy.quorumBps > 0 && refundHost + equalDistribution < Math.ceil((eligibleCount * policy.quorumBps) / 10_000)) {
+    return POLICY_OUTCOMES[policy.quorumDefault];
+  }
+  const margin = Math.max(policy.minMargin, 1);
+  if (refundHost >= equalDistribution + margin) return "refund-host";
+  if (equalDistribution >= refundHost + margin) return "equal-distribution";
+  return POLICY_OUTCOMES[policy.tieBreak];
+}
+
+// Mirrors the program's APPEAL_WINDOW_SECONDS; the vault stays locked until it passes.
+export const APPEAL_WINDOW_SECONDS = 3 * 24 * 60 * 60;
+
+/** Refunds or splits an escrow vault on its dispute outcome; a no-op once settled. */
+export async function settleEscrowVault(campaignId: string, outcome: DisputeOutcome): Promise<void> {
+  const escrow = await inco.getEscrowState(campaignId);
+  if (!escrow || escrow.isClosed || escrow.balance === 0) return;
+  if (outcome === "equal-distribution") {
+    if (!escrow.equalDistributionSettled) await inco.settleEqualDistribution(campaignId);
+  } else {
+    await inco.refundEscrow(campaignId);
+  }
 }
 
 async function resolveDisputeInternal(
@@ -58,7 +90,11 @@ async function resolveDisputeInternal(
   const totals = await inco.decryptVoteTotals(campaignId);
   if (!totals) throw new BadRequestError("Unable to decrypt vote totals");
 
-  const outcome = resolveOutcome(totals.refundHost, totals.equalDistribution);
+  // A pool finalized on-chain has already applied its policy.
+  const outcome =
+    state.outcome !== "pending"
+      ? POLICY_OUTCOMES[state.outcome]
+      : resolveOutcome(totals.refundHost, totals.equalDistribution, state.outcomePolicy, state.eligibleCount);
   const resolvedAt = Date.now();
   const update: Partial<CampaignDoc> = {
     status: outcome === "equal-distribution" ? "winners-announced" : "closed",
@@ -76,6 +112,16 @@ async function resolveDisputeInternal(
     update.selectedWinners = campaign.eligibleHashes;
   }
 
+  // An on-chain escrow settles itself from the finalized pool outcome, once it
+  // can no longer be appealed; until then the dispute scheduler retries.
+  if (state.outcome !== "pending" && campaign.type === "escrow") {
+    try {
+      await settleEscrowVault(campaignId, outcome);
+    } catch (error) {
+      logger.error("Failed to settle escrow vault after dispute", { campaignId, error: String(error) });
+    }
+  }
+
   if ((outcome === "refund-host" || outcome === "tie") && campaign.refundAddress) {
     try {
       const balance = await getCampaignPrivateBalance(campaignId);
@@ -123,7 +169,9 @@ export async function initializeVotingForCampaign(campaignId: string): Promise<v
   const zkVerifierProgram = new PublicKey(env.zk.verifierProgramId);
 
   try {
-    await inco.initializeVotingPool(campaignId, eligibilityRoot, zkVerifierProgram);
+    await inco.initializeVotingPool(campaignId, eligibilityRoot, zkVerifierProgram, {
+      eligibleCount: campaign.eligibleHashes.length,
+    });
   } catch (error: any) {
     if (!error.message?.includes("already in use")) {
       throw error;